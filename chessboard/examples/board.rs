@@ -0,0 +1,131 @@
+//! Plays both sides of a game on an `iced_chessboard::ChessBoard`.
+//!
+//! Run with `cargo run -p iced-chessboard --example board`.
+
+use iced::widget::{button, scrollable, text, Column, Row};
+use iced::{Alignment, Element, Sandbox, Settings, Theme};
+use iced_chessboard::chess960::{Chess960, Chess960Castling};
+use iced_chessboard::{ChessBoard, GameStatus, MoveEvent, Moves};
+use pleco::Board;
+
+fn main() -> iced::Result {
+    BoardExample::run(Settings::default())
+}
+
+struct BoardExample {
+    game: Board,
+    chess960_castling: Option<Chess960Castling>,
+    moves: Vec<String>,
+    black_at_bottom: bool,
+    dark: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Moved(MoveEvent),
+    Flip,
+    ToggleTheme,
+    NewGame,
+    NewChess960Game,
+}
+
+impl Sandbox for BoardExample {
+    type Message = Message;
+
+    fn new() -> Self {
+        Self {
+            game: Board::start_pos(),
+            chess960_castling: None,
+            moves: vec![],
+            black_at_bottom: false,
+            dark: false,
+        }
+    }
+
+    fn title(&self) -> String {
+        String::from("Chess board example")
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::Moved(move_event) => {
+                // The board is controlled, so the move is only shown once it is applied here.
+                let played = Moves::play_uci_move(
+                    &self.game,
+                    self.chess960_castling.as_ref(),
+                    &move_event.uci,
+                );
+                if let Some((game, chess960_castling)) = played {
+                    self.game = game;
+                    self.chess960_castling = chess960_castling;
+                    self.moves.push(move_event.san);
+                }
+            }
+            Message::Flip => self.black_at_bottom = !self.black_at_bottom,
+            Message::ToggleTheme => self.dark = !self.dark,
+            Message::NewGame => {
+                *self = Self {
+                    dark: self.dark,
+                    ..BoardExample::new()
+                }
+            }
+            Message::NewChess960Game => {
+                let (game, chess960_castling) =
+                    Chess960::start_position(Chess960::random_position_number());
+                self.game = game;
+                self.chess960_castling = Some(chess960_castling);
+                self.moves.clear();
+            }
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let mut board = ChessBoard::new(480)
+            .game(self.game.clone())
+            .orientation(self.black_at_bottom)
+            .controlled(true)
+            .on_move(Message::Moved);
+        if let Some(chess960_castling) = self.chess960_castling {
+            board = board.chess960_castling(chess960_castling);
+        }
+
+        let moves = self
+            .moves
+            .chunks(2)
+            .enumerate()
+            .fold(Column::new(), |column, (index, pair)| {
+                column.push(text(format!("{}. {}", index + 1, pair.join(" "))))
+            });
+        let side = Column::new()
+            .spacing(10)
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .push(button(text("Flip")).on_press(Message::Flip))
+                    .push(button(text("Theme")).on_press(Message::ToggleTheme))
+                    .push(button(text("New game")).on_press(Message::NewGame))
+                    .push(button(text("Chess960")).on_press(Message::NewChess960Game)),
+            )
+            .push(text(format!(
+                "Result: {}",
+                GameStatus::of(&self.game).result()
+            )))
+            .push(scrollable(moves).height(380));
+
+        Row::new()
+            .padding(10)
+            .spacing(20)
+            .align_items(Alignment::Start)
+            .push(board)
+            .push(side)
+            .into()
+    }
+
+    fn theme(&self) -> Theme {
+        if self.dark {
+            Theme::Dark
+        } else {
+            Theme::Light
+        }
+    }
+}
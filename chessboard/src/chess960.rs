@@ -0,0 +1,489 @@
+use pleco::core::CastleType;
+use pleco::{Board, File, Piece, PieceType, Player, Rank, SQ};
+use rand::Rng;
+
+use crate::position_setup::{CastlingRight, PositionSetup, SetupError};
+
+pub const POSITIONS_COUNT: u16 = 960;
+
+const KNIGHTS_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chess960Castling {
+    rook_files: [Option<File>; 4],
+}
+
+impl Chess960Castling {
+    pub fn none() -> Self {
+        Self {
+            rook_files: [None; 4],
+        }
+    }
+
+    pub fn rook_file(&self, right: CastlingRight) -> Option<File> {
+        self.rook_files[right as usize]
+    }
+
+    pub fn fen_field(&self) -> String {
+        let field: String = CastlingRight::ALL
+            .into_iter()
+            .filter_map(|right| {
+                self.rook_file(right).map(|file| {
+                    let file_char = (b'a' + file as u8) as char;
+                    if right.player() == Player::White {
+                        file_char.to_ascii_uppercase()
+                    } else {
+                        file_char
+                    }
+                })
+            })
+            .collect();
+        if field.is_empty() {
+            String::from("-")
+        } else {
+            field
+        }
+    }
+
+    pub fn after_move(&self, board: &Board, src: SQ, dest: SQ) -> Self {
+        let mut castling = *self;
+        let moved_piece = board.piece_at_sq(src);
+        CastlingRight::ALL.into_iter().for_each(|right| {
+            let player = right.player();
+            let king_moved = moved_piece == Piece::make_lossy(player, PieceType::K);
+            let rook_square_touched = castling
+                .rook_square(right)
+                .map(|rook_square| rook_square == src || rook_square == dest)
+                .unwrap_or(false);
+            if king_moved || rook_square_touched {
+                castling.rook_files[right as usize] = None;
+            }
+        });
+        castling
+    }
+
+    pub fn after_castling(&self, player: Player) -> Self {
+        let mut castling = *self;
+        CastlingRight::ALL
+            .into_iter()
+            .filter(|right| right.player() == player)
+            .for_each(|right| castling.rook_files[right as usize] = None);
+        castling
+    }
+
+    pub fn castle(&self, board: &Board, right: CastlingRight) -> Option<Board> {
+        let player = right.player();
+        if board.turn() != player {
+            return None;
+        }
+        let back_rank = player.relative_rank(Rank::R1);
+        let rook_square = self.rook_square(right)?;
+        let king_square = board.king_sq(player);
+        if king_square.rank() != back_rank
+            || board.piece_at_sq(rook_square) != Piece::make_lossy(player, PieceType::R)
+        {
+            return None;
+        }
+
+        let (king_target, rook_target) = match right.castle_type() {
+            CastleType::KingSide => (SQ::make(File::G, back_rank), SQ::make(File::F, back_rank)),
+            CastleType::QueenSide => (SQ::make(File::C, back_rank), SQ::make(File::D, back_rank)),
+        };
+
+        let files = [king_square, king_target, rook_square, rook_target]
+            .map(|square| square.file_idx_of_sq());
+        let min_file = *files.iter().min()?;
+        let max_file = *files.iter().max()?;
+        let path_is_free = (min_file..=max_file).all(|file| {
+            let square = SQ(back_rank as u8 * 8 + file);
+            square == king_square || square == rook_square || board.empty(square)
+        });
+        if !path_is_free {
+            return None;
+        }
+
+        let king_from = king_square
+            .file_idx_of_sq()
+            .min(king_target.file_idx_of_sq());
+        let king_to = king_square
+            .file_idx_of_sq()
+            .max(king_target.file_idx_of_sq());
+        let occupied_without_king = board.occupied() ^ king_square.to_bb();
+        let opponent_pieces = board.get_occupied_player(player.other_player());
+        let king_path_is_safe = (king_from..=king_to).all(|file| {
+            let square = SQ(back_rank as u8 * 8 + file);
+            (board.attackers_to(square, occupied_without_king) & opponent_pieces).is_empty()
+        });
+        if !king_path_is_safe {
+            return None;
+        }
+
+        let mut setup = PositionSetup::from_board(board);
+        setup.set_piece_at_sq(king_square, Piece::None);
+        setup.set_piece_at_sq(rook_square, Piece::None);
+        setup.set_piece_at_sq(king_target, Piece::make_lossy(player, PieceType::K));
+        setup.set_piece_at_sq(rook_target, Piece::make_lossy(player, PieceType::R));
+        setup.set_turn(player.other_player());
+        setup.set_en_passant_file(None);
+        let fullmove_number = if player == Player::Black {
+            setup.fullmove_number() + 1
+        } else {
+            setup.fullmove_number()
+        };
+        setup.set_move_counters(setup.halfmove_clock() + 1, fullmove_number);
+
+        setup.validate().ok()
+    }
+
+    pub fn castle_for_drag(
+        &self,
+        board: &Board,
+        src: SQ,
+        dest: SQ,
+        ordinary_move_exists: bool,
+    ) -> Option<(CastlingRight, Board)> {
+        let player = board.turn();
+        if board.piece_at_sq(src) != Piece::make_lossy(player, PieceType::K) {
+            return None;
+        }
+        CastlingRight::ALL
+            .into_iter()
+            .filter(|right| right.player() == player)
+            .find_map(|right| {
+                let rook_square = self.rook_square(right)?;
+                let king_target_file = match right.castle_type() {
+                    CastleType::KingSide => File::G,
+                    CastleType::QueenSide => File::C,
+                };
+                let king_target = SQ::make(king_target_file, player.relative_rank(Rank::R1));
+                let dropped_on_rook = dest == rook_square;
+                let dropped_on_target = dest == king_target && !ordinary_move_exists;
+                if dropped_on_rook || dropped_on_target {
+                    self.castle(board, right)
+                        .map(|new_board| (right, new_board))
+                } else {
+                    None
+                }
+            })
+    }
+
+    pub fn rook_square(&self, right: CastlingRight) -> Option<SQ> {
+        self.rook_file(right)
+            .map(|file| SQ::make(file, right.player().relative_rank(Rank::R1)))
+    }
+}
+
+pub struct Chess960 {}
+
+impl Chess960 {
+    pub fn random_position_number() -> u16 {
+        rand::thread_rng().gen_range(0..POSITIONS_COUNT)
+    }
+
+    pub fn back_rank(position_number: u16) -> [PieceType; 8] {
+        let mut back_rank = [PieceType::None; 8];
+        let number = (position_number % POSITIONS_COUNT) as usize;
+
+        let light_bishop_file = (number % 4) * 2 + 1;
+        let number = number / 4;
+        let dark_bishop_file = (number % 4) * 2;
+        let number = number / 4;
+        back_rank[light_bishop_file] = PieceType::B;
+        back_rank[dark_bishop_file] = PieceType::B;
+
+        let queen_index = number % 6;
+        let (first_knight_index, second_knight_index) = KNIGHTS_PLACEMENTS[number / 6];
+
+        let free_files = |back_rank: &[PieceType; 8]| -> Vec<usize> {
+            (0..8)
+                .filter(|file| back_rank[*file] == PieceType::None)
+                .collect()
+        };
+
+        let queen_file = free_files(&back_rank)[queen_index];
+        back_rank[queen_file] = PieceType::Q;
+
+        let remaining_files = free_files(&back_rank);
+        back_rank[remaining_files[first_knight_index]] = PieceType::N;
+        back_rank[remaining_files[second_knight_index]] = PieceType::N;
+
+        let remaining_files = free_files(&back_rank);
+        back_rank[remaining_files[0]] = PieceType::R;
+        back_rank[remaining_files[1]] = PieceType::K;
+        back_rank[remaining_files[2]] = PieceType::R;
+
+        back_rank
+    }
+
+    pub fn start_position(position_number: u16) -> (Board, Chess960Castling) {
+        let back_rank = Chess960::back_rank(position_number);
+        let mut setup = PositionSetup::empty();
+        let mut castling = Chess960Castling::none();
+
+        let king_file = back_rank
+            .iter()
+            .position(|piece_type| *piece_type == PieceType::K)
+            .unwrap_or(4);
+        back_rank.iter().enumerate().for_each(|(file, piece_type)| {
+            let file_square = SQ(file as u8);
+            let pleco_file = file_square.file();
+            setup.set_piece_at_sq(
+                SQ::make(pleco_file, Rank::R1),
+                Piece::make_lossy(Player::White, *piece_type),
+            );
+            setup.set_piece_at_sq(SQ::make(pleco_file, Rank::R2), Piece::WhitePawn);
+            setup.set_piece_at_sq(SQ::make(pleco_file, Rank::R7), Piece::BlackPawn);
+            setup.set_piece_at_sq(
+                SQ::make(pleco_file, Rank::R8),
+                Piece::make_lossy(Player::Black, *piece_type),
+            );
+            if *piece_type == PieceType::R {
+                let (white_right, black_right) = if file > king_file {
+                    (CastlingRight::WhiteShort, CastlingRight::BlackShort)
+                } else {
+                    (CastlingRight::WhiteLong, CastlingRight::BlackLong)
+                };
+                castling.rook_files[white_right as usize] = Some(pleco_file);
+                castling.rook_files[black_right as usize] = Some(pleco_file);
+            }
+        });
+
+        let board = setup
+            .validate()
+            .expect("Chess960 start positions are always valid");
+        (board, castling)
+    }
+
+    pub fn fen(board: &Board, castling: &Chess960Castling) -> String {
+        let mut fields: Vec<String> = board.fen().split(' ').map(String::from).collect();
+        if fields.len() > 2 {
+            fields[2] = castling.fen_field();
+        }
+        fields.join(" ")
+    }
+
+    /// Reads a standard FEN, or a Chess960 one when the castling rights need rook files or do
+    /// not match the standard king and rook squares, as in Shredder-FEN and X-FEN.
+    pub fn any_position_from_fen(
+        fen: &str,
+    ) -> Result<(Board, Option<Chess960Castling>), SetupError> {
+        match PositionSetup::board_from_fen(fen) {
+            Ok(board) => Ok((board, None)),
+            Err(SetupError::InvalidCastlingField(_))
+            | Err(SetupError::CastlingWithoutKing(_))
+            | Err(SetupError::CastlingWithoutRook(_)) => {
+                Chess960::position_from_fen(fen).map(|(board, castling)| (board, Some(castling)))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn position_from_fen(fen: &str) -> Result<(Board, Chess960Castling), SetupError> {
+        let mut fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() < 4 || fields.len() > 6 {
+            return Err(SetupError::SectionsCount(fields.len()));
+        }
+        let castling_field = fields[2];
+        fields[2] = "-";
+        let setup = PositionSetup::from_fen(&fields.join(" "))?;
+        let invalid_castling = || SetupError::InvalidCastlingField(castling_field.to_string());
+
+        let mut castling = Chess960Castling::none();
+        if castling_field != "-" {
+            for castling_char in castling_field.chars() {
+                let player = if castling_char.is_ascii_uppercase() {
+                    Player::White
+                } else {
+                    Player::Black
+                };
+                let back_rank = player.relative_rank(Rank::R1);
+                let king = Piece::make_lossy(player, PieceType::K);
+                let rook = Piece::make_lossy(player, PieceType::R);
+                let king_file = (0..8u8)
+                    .find(|file| setup.piece_at_sq(SQ(back_rank as u8 * 8 + file)) == king)
+                    .ok_or_else(invalid_castling)?;
+                let is_rook_file =
+                    |file: &u8| setup.piece_at_sq(SQ(back_rank as u8 * 8 + file)) == rook;
+
+                let rook_file = match castling_char.to_ascii_lowercase() {
+                    'k' => (king_file + 1..8).rev().find(is_rook_file),
+                    'q' => (0..king_file).find(is_rook_file),
+                    file_char @ 'a'..='h' => Some(file_char as u8 - b'a'),
+                    _ => None,
+                }
+                .ok_or_else(invalid_castling)?;
+                if rook_file == king_file {
+                    return Err(invalid_castling());
+                }
+
+                let right = match (player, rook_file > king_file) {
+                    (Player::White, true) => CastlingRight::WhiteShort,
+                    (Player::White, false) => CastlingRight::WhiteLong,
+                    (Player::Black, true) => CastlingRight::BlackShort,
+                    (Player::Black, false) => CastlingRight::BlackLong,
+                };
+                if !is_rook_file(&rook_file) {
+                    return Err(SetupError::CastlingWithoutRook(right));
+                }
+                if castling.rook_files[right as usize].is_some() {
+                    return Err(invalid_castling());
+                }
+                castling.rook_files[right as usize] = Some(SQ(rook_file).file());
+            }
+        }
+
+        let board = setup.validate()?;
+        Ok((board, castling))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn back_rank_string(position_number: u16) -> String {
+        Chess960::back_rank(position_number)
+            .iter()
+            .map(|piece_type| match piece_type {
+                PieceType::K => 'K',
+                PieceType::Q => 'Q',
+                PieceType::R => 'R',
+                PieceType::B => 'B',
+                PieceType::N => 'N',
+                _ => '?',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn position_numbers_map_to_back_ranks() {
+        assert_eq!(back_rank_string(0), "BBQNNRKR");
+        assert_eq!(back_rank_string(518), "RNBQKBNR");
+        assert_eq!(back_rank_string(959), "RKRNNQBB");
+    }
+
+    #[test]
+    fn start_positions_have_shredder_castling_fields() {
+        let (board, castling) = Chess960::start_position(518);
+        assert_eq!(
+            Chess960::fen(&board, &castling),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1"
+        );
+        let (board, castling) = Chess960::start_position(0);
+        assert_eq!(
+            Chess960::fen(&board, &castling),
+            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1"
+        );
+    }
+
+    #[test]
+    fn shredder_castling_fields_round_trip() {
+        (0..POSITIONS_COUNT)
+            .step_by(37)
+            .for_each(|position_number| {
+                let (board, castling) = Chess960::start_position(position_number);
+                let fen = Chess960::fen(&board, &castling);
+                let (read_board, read_castling) = Chess960::position_from_fen(&fen).unwrap();
+                assert_eq!(read_castling, castling, "{}", fen);
+                assert_eq!(Chess960::fen(&read_board, &read_castling), fen);
+            });
+    }
+
+    #[test]
+    fn x_fen_castling_letters_use_the_outermost_rooks() {
+        let x_fen = "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1";
+        let (_, castling) = Chess960::position_from_fen(x_fen).unwrap();
+        assert_eq!(castling.fen_field(), "HFhf");
+    }
+
+    #[test]
+    fn any_position_detects_chess960_from_the_pieces() {
+        let standard = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(Chess960::any_position_from_fen(standard).unwrap().1, None);
+
+        let x_fen = "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1";
+        let (_, castling) = Chess960::any_position_from_fen(x_fen).unwrap();
+        assert_eq!(
+            castling.map(|castling| castling.fen_field()),
+            Some("HFhf".into())
+        );
+
+        let shredder = "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1";
+        let (_, castling) = Chess960::any_position_from_fen(shredder).unwrap();
+        assert_eq!(
+            castling.map(|castling| castling.fen_field()),
+            Some("HFhf".into())
+        );
+
+        let missing_king = "8/8/8/8/8/8/8/R6R w KQ - 0 1";
+        assert!(Chess960::any_position_from_fen(missing_king).is_err());
+    }
+
+    #[test]
+    fn castling_moves_king_and_rook() {
+        let (board, castling) =
+            Chess960::position_from_fen("k7/8/8/8/8/8/8/1R2K2R w HB - 0 1").unwrap();
+        let short = castling.castle(&board, CastlingRight::WhiteShort).unwrap();
+        assert_eq!(short.fen(), "k7/8/8/8/8/8/8/1R3RK1 b - - 1 1");
+        let long = castling.castle(&board, CastlingRight::WhiteLong).unwrap();
+        assert_eq!(long.fen(), "k7/8/8/8/8/8/8/2KR3R b - - 1 1");
+        assert_eq!(castling.castle(&board, CastlingRight::BlackShort), None);
+    }
+
+    #[test]
+    fn castling_out_of_through_or_into_check_is_rejected() {
+        let cases = [
+            // The king is in check.
+            "4r1k1/8/8/8/8/8/8/4K2R w H - 0 1",
+            // The king passes through an attacked square.
+            "5rk1/8/8/8/8/8/8/4K2R w H - 0 1",
+            // The king lands on an attacked square.
+            "k5r1/8/8/8/8/8/8/4K2R w H - 0 1",
+        ];
+        for fen in cases {
+            let (board, castling) = Chess960::position_from_fen(fen).unwrap();
+            assert_eq!(
+                castling.castle(&board, CastlingRight::WhiteShort),
+                None,
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn castling_needs_an_empty_path() {
+        let (board, castling) =
+            Chess960::position_from_fen("4k3/8/8/8/8/8/8/4KB1R w H - 0 1").unwrap();
+        assert_eq!(castling.castle(&board, CastlingRight::WhiteShort), None);
+    }
+
+    #[test]
+    fn dropping_the_king_on_its_rook_castles() {
+        let (board, castling) =
+            Chess960::position_from_fen("4k3/8/8/8/8/8/8/RK5R w HA - 0 1").unwrap();
+        let (right, short) = castling
+            .castle_for_drag(&board, SQ::B1, SQ::H1, false)
+            .unwrap();
+        assert_eq!(right, CastlingRight::WhiteShort);
+        assert_eq!(short.fen(), "4k3/8/8/8/8/8/8/R4RK1 b - - 1 1");
+        let (right, long) = castling
+            .castle_for_drag(&board, SQ::B1, SQ::A1, false)
+            .unwrap();
+        assert_eq!(right, CastlingRight::WhiteLong);
+        assert_eq!(long.fen(), "4k3/8/8/8/8/8/8/2KR3R b - - 1 1");
+        // With an ordinary king move to c1 available, dropping there plays that move instead.
+        assert_eq!(castling.castle_for_drag(&board, SQ::B1, SQ::C1, true), None);
+    }
+}
@@ -2,7 +2,8 @@ use std::marker::PhantomData;
 
 use super::utils::Utils;
 
-use super::ChessBoard;
+use super::{ChessBoard, State};
+use crate::style::{Appearance, StyleSheet};
 
 use iced::{Color, Font, Rectangle};
 
@@ -16,6 +17,7 @@ use pleco::{Piece, Player, SQ};
 pub struct DrawingHelper<Message, Renderer>
 where
     Renderer: renderer::Renderer + text::Renderer<Font = Font> + svg::Renderer,
+    Renderer::Theme: StyleSheet,
 {
     _renderer: PhantomData<Renderer>,
     _msg: PhantomData<Message>,
@@ -24,8 +26,9 @@ where
 impl<Message, Renderer> DrawingHelper<Message, Renderer>
 where
     Renderer: renderer::Renderer + text::Renderer<Font = Font> + svg::Renderer,
+    Renderer::Theme: StyleSheet,
 {
-    pub fn draw_background(board: &ChessBoard<Message>, renderer: &mut Renderer, bounds: Rectangle) {
+    pub fn draw_background(appearance: &Appearance, renderer: &mut Renderer, bounds: Rectangle) {
         renderer.fill_quad(
             renderer::Quad {
                 bounds,
@@ -33,47 +36,53 @@ where
                 border_width: 0f32,
                 border_radius: BorderRadius::default(),
             },
-            board.background_color,
+            appearance.background,
         );
     }
 
-    pub fn draw_cells(board: &ChessBoard<Message>, renderer: &mut Renderer, bounds: Rectangle) {
+    pub fn draw_cells(
+        board: &ChessBoard<Message, Renderer>,
+        state: &State,
+        appearance: &Appearance,
+        renderer: &mut Renderer,
+        bounds: Rectangle,
+    ) {
         let cells_size = (board.size as f32) * 0.111;
         (0..8).for_each(|row| {
             (0..8).for_each(|col| {
                 let file = if board.reversed { 7 - col } else { col };
                 let rank = if board.reversed { row } else { 7 - row };
 
-                let is_start_cell = match board.drag_and_drop_data.clone() {
+                let is_start_cell = match state.drag_and_drop_data.clone() {
                     Some(dnd_data) => dnd_data.start_file == file && dnd_data.start_rank == rank,
                     _ => false,
                 };
 
-                let is_end_cell = match board.drag_and_drop_data.clone() {
+                let is_end_cell = match state.drag_and_drop_data.clone() {
                     Some(dnd_data) => dnd_data.end_file == file && dnd_data.end_rank == rank,
                     _ => false,
                 };
 
-                let is_cross_cell = match board.drag_and_drop_data.clone() {
+                let is_cross_cell = match state.drag_and_drop_data.clone() {
                     Some(dnd_data) => dnd_data.end_file == file || dnd_data.end_rank == rank,
                     _ => false,
                 };
 
                 let is_white_cell = (row + col) % 2 == 0;
                 let mut cell_color = if is_white_cell {
-                    board.white_cell_color
+                    appearance.light_squares
                 } else {
-                    board.black_cell_color
+                    appearance.dark_squares
                 };
 
                 if is_cross_cell {
-                    cell_color = board.dnd_cross_cells_color;
+                    cell_color = appearance.drag_cross;
                 }
                 if is_start_cell {
-                    cell_color = board.dnd_start_cell_color;
+                    cell_color = appearance.drag_start;
                 }
                 if is_end_cell {
-                    cell_color = board.dnd_end_cell_color;
+                    cell_color = appearance.drag_end;
                 }
 
                 let x = cells_size * (col as f32 + 0.5) + bounds.x;
@@ -97,7 +106,12 @@ where
         });
     }
 
-    pub fn draw_coordinates(board: &ChessBoard<Message>, renderer: &mut Renderer, bounds: Rectangle) {
+    pub fn draw_coordinates(
+        board: &ChessBoard<Message, Renderer>,
+        appearance: &Appearance,
+        renderer: &mut Renderer,
+        bounds: Rectangle,
+    ) {
         let files = vec!["A", "B", "C", "D", "E", "F", "G", "H"];
         let ranks = vec!["8", "7", "6", "5", "4", "3", "2", "1"];
 
@@ -110,14 +124,14 @@ where
             let y2 = cells_size * 8.75 + bounds.y;
 
             let text1 = Text {
-                content: file_str.into(),
+                content: file_str,
                 bounds: Rectangle {
                     x,
                     y: y1,
                     width: font_size,
                     height: font_size,
                 },
-                color: board.text_color,
+                color: appearance.coordinates,
                 size: font_size,
                 font: Font::default(),
                 horizontal_alignment: iced::alignment::Horizontal::Center,
@@ -126,14 +140,14 @@ where
             renderer.fill_text(text1);
 
             let text2 = Text {
-                content: file_str.into(),
+                content: file_str,
                 bounds: Rectangle {
                     x,
                     y: y2,
                     width: font_size,
                     height: font_size,
                 },
-                color: board.text_color,
+                color: appearance.coordinates,
                 size: font_size,
                 font: Font::default(),
                 horizontal_alignment: iced::alignment::Horizontal::Center,
@@ -151,14 +165,14 @@ where
                 cells_size * (if board.reversed { 7 - row } else { row } as f32 + 1.0) + bounds.y;
 
             let text1 = Text {
-                content: rank_str.into(),
+                content: rank_str,
                 bounds: Rectangle {
                     x: x1,
-                    y: y,
+                    y,
                     width: font_size,
                     height: font_size,
                 },
-                color: board.text_color,
+                color: appearance.coordinates,
                 size: font_size,
                 font: Font::default(),
                 horizontal_alignment: iced::alignment::Horizontal::Center,
@@ -167,14 +181,14 @@ where
             renderer.fill_text(text1);
 
             let text2 = Text {
-                content: rank_str.into(),
+                content: rank_str,
                 bounds: Rectangle {
                     x: x2,
                     y,
                     width: font_size,
                     height: font_size,
                 },
-                color: board.text_color,
+                color: appearance.coordinates,
                 size: font_size,
                 font: Font::default(),
                 horizontal_alignment: iced::alignment::Horizontal::Center,
@@ -184,11 +198,15 @@ where
         });
     }
 
-    pub fn draw_player_turn(board: &ChessBoard<Message>, renderer: &mut Renderer, bounds: Rectangle) {
+    pub fn draw_player_turn(
+        board: &ChessBoard<Message, Renderer>,
+        renderer: &mut Renderer,
+        bounds: Rectangle,
+    ) {
         let cells_size = (board.size as f32) * 0.111;
         let x = cells_size * 8.5 + bounds.x;
         let y = cells_size * 8.5 + bounds.y;
-        let color = if board.logic.turn() == Player::White {
+        let color = if board.turn() == Player::White {
             Color::WHITE
         } else {
             Color::BLACK
@@ -209,7 +227,12 @@ where
         );
     }
 
-    pub fn draw_pieces(board: &ChessBoard<Message>, renderer: &mut Renderer, bounds: Rectangle) {
+    pub fn draw_pieces(
+        board: &ChessBoard<Message, Renderer>,
+        state: &State,
+        renderer: &mut Renderer,
+        bounds: Rectangle,
+    ) {
         let cells_size = (board.size as f32) * 0.111;
 
         (0..8).for_each(|row| {
@@ -217,7 +240,7 @@ where
                 let file = if board.reversed { 7 - col } else { col };
                 let rank = if board.reversed { row } else { 7 - row };
 
-                let is_the_moved_piece = match board.drag_and_drop_data.clone() {
+                let is_the_moved_piece = match state.drag_and_drop_data.clone() {
                     Some(dnd_data) => dnd_data.start_file == file && dnd_data.start_rank == rank,
                     _ => false,
                 };
@@ -225,9 +248,11 @@ where
                 if !is_the_moved_piece {
                     let pleco_file = Utils::coord_file_to_pleco_file(file as i32);
                     let pleco_rank = Utils::coord_rank_to_pleco_rank(rank as i32);
-                    let piece = board.logic.piece_at_sq(SQ::make(pleco_file, pleco_rank));
+                    let piece = board.piece_at_sq(SQ::make(pleco_file, pleco_rank));
                     let piece_image_handle =
-                        DrawingHelper::<Message, Renderer>::pleco_piece_to_image_handle(board, piece);
+                        DrawingHelper::<Message, Renderer>::pleco_piece_to_image_handle(
+                            board, piece,
+                        );
                     if let Some(piece_image_handle) = piece_image_handle {
                         let cell_bounds = Rectangle {
                             x: cells_size * (0.5f32 + col as f32) + bounds.x,
@@ -242,18 +267,24 @@ where
         });
     }
 
-    pub fn draw_moved_piece(board: &ChessBoard<Message>, renderer: &mut Renderer, bounds: Rectangle) {
+    pub fn draw_moved_piece(
+        board: &ChessBoard<Message, Renderer>,
+        state: &State,
+        renderer: &mut Renderer,
+        bounds: Rectangle,
+    ) {
         let cells_size = (board.size as f32) * 0.111;
 
-        if board.drag_and_drop_data.is_some() {
-            let piece_image_handle = DrawingHelper::<Message, Renderer>::pleco_piece_to_image_handle(
-                board,
-                board.drag_and_drop_data.clone().unwrap().moved_piece,
-            );
+        if state.drag_and_drop_data.is_some() {
+            let piece_image_handle =
+                DrawingHelper::<Message, Renderer>::pleco_piece_to_image_handle(
+                    board,
+                    state.drag_and_drop_data.clone().unwrap().moved_piece,
+                );
             if let Some(piece_image_handle) = piece_image_handle {
                 let cell_bounds = Rectangle {
-                    x: board.mouse_x + bounds.x,
-                    y: board.mouse_y + bounds.y,
+                    x: state.mouse_x + bounds.x,
+                    y: state.mouse_y + bounds.y,
                     width: cells_size,
                     height: cells_size,
                 };
@@ -262,21 +293,110 @@ where
         }
     }
 
-    fn pleco_piece_to_image_handle(board: &ChessBoard<Message>, piece: Piece) -> Option<Handle> {
-        match piece {
-            Piece::None => None,
-            Piece::WhitePawn => Some(board.pieces_images.svg_wp.clone()),
-            Piece::WhiteKnight => Some(board.pieces_images.svg_wn.clone()),
-            Piece::WhiteBishop => Some(board.pieces_images.svg_wb.clone()),
-            Piece::WhiteRook => Some(board.pieces_images.svg_wr.clone()),
-            Piece::WhiteQueen => Some(board.pieces_images.svg_wq.clone()),
-            Piece::WhiteKing => Some(board.pieces_images.svg_wk.clone()),
-            Piece::BlackPawn => Some(board.pieces_images.svg_bp.clone()),
-            Piece::BlackKnight => Some(board.pieces_images.svg_bn.clone()),
-            Piece::BlackBishop => Some(board.pieces_images.svg_bb.clone()),
-            Piece::BlackRook => Some(board.pieces_images.svg_br.clone()),
-            Piece::BlackQueen => Some(board.pieces_images.svg_bq.clone()),
-            Piece::BlackKing => Some(board.pieces_images.svg_bk.clone()),
+    pub fn draw_arrow(
+        board: &ChessBoard<Message, Renderer>,
+        appearance: &Appearance,
+        renderer: &mut Renderer,
+        bounds: Rectangle,
+    ) {
+        if let Some((src, dest)) = board.arrow {
+            let cells_size = (board.size as f32) * 0.111;
+            let cell_center = |square: SQ| {
+                let file = square.file_idx_of_sq() as f32;
+                let rank = square.rank_idx_of_sq() as f32;
+                let col = if board.reversed { 7.0 - file } else { file };
+                let row = if board.reversed { rank } else { 7.0 - rank };
+                (col + 0.5, row + 0.5)
+            };
+            let (x1, y1) = cell_center(src);
+            let (x2, y2) = cell_center(dest);
+            let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+            if length == 0.0 {
+                return;
+            }
+            let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+            let head_length = 0.4;
+            let head_half_width = 0.25;
+            let (base_x, base_y) = (x2 - dx * head_length, y2 - dy * head_length);
+
+            let color = appearance.arrow;
+            let svg_content = format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 8 8">
+<line x1="{x1}" y1="{y1}" x2="{base_x}" y2="{base_y}" stroke="rgb({r},{g},{b})" stroke-opacity="{a}" stroke-width="0.15" stroke-linecap="round"/>
+<polygon points="{x2},{y2} {lx},{ly} {rx},{ry}" fill="rgb({r},{g},{b})" fill-opacity="{a}"/>
+</svg>"#,
+                lx = base_x - dy * head_half_width,
+                ly = base_y + dx * head_half_width,
+                rx = base_x + dy * head_half_width,
+                ry = base_y - dx * head_half_width,
+                r = (color.r * 255.0) as u8,
+                g = (color.g * 255.0) as u8,
+                b = (color.b * 255.0) as u8,
+                a = color.a,
+            );
+            let arrow_bounds = Rectangle {
+                x: cells_size * 0.5 + bounds.x,
+                y: cells_size * 0.5 + bounds.y,
+                width: cells_size * 8.0,
+                height: cells_size * 8.0,
+            };
+            renderer.draw(
+                Handle::from_memory(svg_content.into_bytes()),
+                None,
+                arrow_bounds,
+            );
         }
     }
+
+    pub fn draw_glyph_badge(
+        board: &ChessBoard<Message, Renderer>,
+        appearance: &Appearance,
+        renderer: &mut Renderer,
+        bounds: Rectangle,
+    ) {
+        if let Some((square, ref glyph)) = board.glyph {
+            let cells_size = (board.size as f32) * 0.111;
+            let file = square.file_idx_of_sq() as f32;
+            let rank = square.rank_idx_of_sq() as f32;
+            let col = if board.reversed { 7.0 - file } else { file };
+            let row = if board.reversed { rank } else { 7.0 - rank };
+            let badge_size = cells_size * 0.45;
+            let badge_width = badge_size.max(badge_size * 0.4 * glyph.chars().count() as f32);
+            let badge_bounds = Rectangle {
+                x: cells_size * (1.5 + col) - badge_width * 0.75 + bounds.x,
+                y: cells_size * (0.5 + row) - badge_size * 0.25 + bounds.y,
+                width: badge_width,
+                height: badge_size,
+            };
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: badge_bounds,
+                    border_radius: BorderRadius::from(badge_size * 0.5),
+                    border_width: 1f32,
+                    border_color: Color::WHITE,
+                },
+                appearance.glyph,
+            );
+            renderer.fill_text(Text {
+                content: glyph,
+                bounds: Rectangle {
+                    x: badge_bounds.center_x(),
+                    y: badge_bounds.center_y(),
+                    ..badge_bounds
+                },
+                color: Color::WHITE,
+                size: badge_size * 0.8,
+                font: Font::default(),
+                horizontal_alignment: iced::alignment::Horizontal::Center,
+                vertical_alignment: iced::alignment::Vertical::Center,
+            });
+        }
+    }
+
+    fn pleco_piece_to_image_handle(
+        board: &ChessBoard<Message, Renderer>,
+        piece: Piece,
+    ) -> Option<Handle> {
+        board.pieces_images.piece_handle(piece)
+    }
 }
@@ -0,0 +1,448 @@
+mod drawing_helper;
+use drawing_helper::DrawingHelper;
+
+mod pieces_vectors;
+pub use pieces_vectors::PiecesVectors;
+
+mod mouse_handler;
+use mouse_handler::MouseHandler;
+
+mod utils;
+
+use iced::event::Status;
+use iced::Event::Mouse;
+use iced::{Element, Font, Length, Point, Rectangle, Size};
+use iced_native::widget::{tree, Tree};
+use iced_native::{layout, mouse, renderer, svg, text, Widget};
+
+use pleco::{Board, Piece, Player, SQ};
+
+use crate::chess960::Chess960Castling;
+use crate::game::{MovableSides, MoveEvent};
+use crate::position_setup::PositionSetup;
+use crate::style::StyleSheet;
+
+#[derive(Clone)]
+struct DragAndDropData {
+    start_file: i8,
+    start_rank: i8,
+    end_file: i8,
+    end_rank: i8,
+    moved_piece: Piece,
+}
+
+/// The pointer and the piece being dragged. They live in the widget tree rather than in
+/// `ChessBoard`, which the application rebuilds on every `view`, so that a drag survives
+/// messages such as clock ticks arriving while the mouse button is held.
+struct State {
+    mouse_x: f32,
+    mouse_y: f32,
+    drag_and_drop_data: Option<DragAndDropData>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            mouse_x: f32::INFINITY,
+            mouse_y: f32::INFINITY,
+            drag_and_drop_data: None,
+        }
+    }
+}
+
+/// A chess board where the user plays moves by dragging pieces.
+///
+/// Moves can only be played once `on_move` or `on_new_position` is set.
+pub struct ChessBoard<'a, Message, Renderer = iced::Renderer>
+where
+    Renderer: renderer::Renderer,
+    Renderer::Theme: StyleSheet,
+{
+    size: u16,
+    style: <Renderer::Theme as StyleSheet>::Style,
+    pieces_images: PiecesVectors,
+    game: Board,
+    chess960_castling: Option<Chess960Castling>,
+    movable: MovableSides,
+    reversed: bool,
+    controlled: bool,
+    on_new_position: Option<Box<dyn Fn(String) -> Message + 'a>>,
+    on_move: Option<Box<dyn Fn(MoveEvent) -> Message + 'a>>,
+    setup_position: Option<PositionSetup>,
+    on_setup_cell_pressed: Option<Box<dyn Fn(SQ) -> Message + 'a>>,
+    arrow: Option<(SQ, SQ)>,
+    glyph: Option<(SQ, String)>,
+}
+
+impl<'a, Message, Renderer> ChessBoard<'a, Message, Renderer>
+where
+    Renderer: renderer::Renderer,
+    Renderer::Theme: StyleSheet,
+{
+    /// Creates a board `size` pixels wide showing the standard starting position.
+    pub fn new(size: u16) -> Self {
+        Self {
+            size,
+            style: Default::default(),
+            pieces_images: PiecesVectors::new(),
+            game: Board::default(),
+            chess960_castling: None,
+            movable: MovableSides::Both,
+            reversed: false,
+            controlled: false,
+            on_new_position: None,
+            on_move: None,
+            setup_position: None,
+            on_setup_cell_pressed: None,
+            arrow: None,
+            glyph: None,
+        }
+    }
+
+    /// Sets the style the theme draws the board with.
+    pub fn style(mut self, style: impl Into<<Renderer::Theme as StyleSheet>::Style>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    /// Sets the position shown and played from.
+    pub fn game(mut self, game: Board) -> Self {
+        self.game = game;
+        self
+    }
+
+    /// Plays the position as Chess960, with these castling rights.
+    pub fn chess960_castling(mut self, chess960_castling: Chess960Castling) -> Self {
+        self.chess960_castling = Some(chess960_castling);
+        self
+    }
+
+    /// Restricts the pieces the user may pick up.
+    pub fn movable_sides(mut self, movable: MovableSides) -> Self {
+        self.movable = movable;
+        self
+    }
+
+    pub fn orientation(mut self, black_at_bottom: bool) -> Self {
+        self.reversed = black_at_bottom;
+        self
+    }
+
+    /// In controlled mode a dropped piece only proposes its move through the callbacks and the
+    /// board keeps its position until a new one is passed to `game`.
+    pub fn controlled(mut self, controlled: bool) -> Self {
+        self.controlled = controlled;
+        self
+    }
+
+    /// Receives the FEN of the position after each move.
+    pub fn on_new_position(mut self, on_new_position: impl Fn(String) -> Message + 'a) -> Self {
+        self.on_new_position = Some(Box::new(on_new_position));
+        self
+    }
+
+    /// Receives each move played on the board.
+    pub fn on_move(mut self, on_move: impl Fn(MoveEvent) -> Message + 'a) -> Self {
+        self.on_move = Some(Box::new(on_move));
+        self
+    }
+
+    /// Shows a position being set up instead of the game. Pressing a square is reported
+    /// through `on_setup_cell_pressed`.
+    pub fn setup_position(mut self, setup_position: PositionSetup) -> Self {
+        self.setup_position = Some(setup_position);
+        self
+    }
+
+    pub fn on_setup_cell_pressed(
+        mut self,
+        on_setup_cell_pressed: impl Fn(SQ) -> Message + 'a,
+    ) -> Self {
+        self.on_setup_cell_pressed = Some(Box::new(on_setup_cell_pressed));
+        self
+    }
+
+    /// Draws an arrow from `src` to `dest`, e.g. to show a hint.
+    pub fn arrow(mut self, src: SQ, dest: SQ) -> Self {
+        self.arrow = Some((src, dest));
+        self
+    }
+
+    /// Draws an annotation glyph such as "!?" in a badge on `square`.
+    pub fn glyph(mut self, square: SQ, glyph: String) -> Self {
+        self.glyph = Some((square, glyph));
+        self
+    }
+
+    fn piece_at_sq(&self, square: SQ) -> Piece {
+        match self.setup_position {
+            Some(ref setup_position) => setup_position.piece_at_sq(square),
+            None => self.game.piece_at_sq(square),
+        }
+    }
+
+    fn turn(&self) -> Player {
+        match self.setup_position {
+            Some(ref setup_position) => setup_position.turn(),
+            None => self.game.turn(),
+        }
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for ChessBoard<'a, Message, Renderer>
+where
+    Renderer: renderer::Renderer + text::Renderer<Font = Font> + svg::Renderer,
+    Renderer::Theme: StyleSheet,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn width(&self) -> Length {
+        Length::Fixed(self.size as f32)
+    }
+
+    fn height(&self) -> Length {
+        Length::Fixed(self.size as f32)
+    }
+
+    fn layout(
+        &self,
+        _renderer: &Renderer,
+        _limits: &iced_native::layout::Limits,
+    ) -> iced_native::layout::Node {
+        layout::Node::new(Size::new(self.size as f32, self.size as f32))
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        _style: &iced_native::renderer::Style,
+        layout: iced_native::Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_ref::<State>();
+        let appearance = theme.appearance(&self.style);
+
+        DrawingHelper::<Message, Renderer>::draw_background(&appearance, renderer, bounds);
+        DrawingHelper::draw_cells(self, state, &appearance, renderer, bounds);
+        DrawingHelper::draw_coordinates(self, &appearance, renderer, bounds);
+        DrawingHelper::draw_player_turn(self, renderer, bounds);
+        DrawingHelper::draw_pieces(self, state, renderer, bounds);
+        DrawingHelper::draw_arrow(self, &appearance, renderer, bounds);
+        DrawingHelper::draw_glyph_badge(self, &appearance, renderer, bounds);
+        DrawingHelper::draw_moved_piece(self, state, renderer, bounds);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: iced::Event,
+        layout: iced_native::Layout<'_>,
+        _cursor_position: Point,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn iced_native::Clipboard,
+        shell: &mut iced_native::Shell<'_, Message>,
+    ) -> iced::event::Status {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_mut::<State>();
+        match event {
+            Mouse(event) => match event {
+                mouse::Event::ButtonPressed(mouse::Button::Left) => {
+                    if self.setup_position.is_some() {
+                        MouseHandler::handle_setup_button_pressed(self, state, shell);
+                    } else {
+                        MouseHandler::handle_left_button_pressed(self, state);
+                    }
+                    Status::Captured
+                }
+                mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                    MouseHandler::handle_left_button_released(self, state, shell);
+                    Status::Captured
+                }
+                mouse::Event::CursorMoved { position } => {
+                    let x = position.x - bounds.x;
+                    let y = position.y - bounds.y;
+                    state.mouse_x = x;
+                    state.mouse_y = y;
+                    MouseHandler::handle_mouse_moved(self, state);
+                    Status::Captured
+                }
+                _ => Status::Ignored,
+            },
+            _ => Status::Ignored,
+        }
+    }
+}
+
+impl<'a, Message: 'a, Renderer> From<ChessBoard<'a, Message, Renderer>>
+    for Element<'a, Message, Renderer>
+where
+    Renderer: renderer::Renderer + text::Renderer<Font = Font> + svg::Renderer + 'a,
+    Renderer::Theme: StyleSheet,
+{
+    fn from(board: ChessBoard<'a, Message, Renderer>) -> Self {
+        Self::new(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use iced::{Background, Color, Theme, Vector};
+    use iced_native::clipboard;
+    use iced_native::event::Event;
+    use iced_native::text::Hit;
+    use iced_native::{Layout, Shell};
+
+    const SIZE: u16 = 400;
+
+    /// Draws nothing, like `renderer::Null`, but also accepts the board's SVG pieces.
+    struct TestRenderer;
+
+    impl renderer::Renderer for TestRenderer {
+        type Theme = Theme;
+
+        fn with_layer(&mut self, _bounds: Rectangle, _f: impl FnOnce(&mut Self)) {}
+
+        fn with_translation(&mut self, _translation: Vector, _f: impl FnOnce(&mut Self)) {}
+
+        fn fill_quad(&mut self, _quad: renderer::Quad, _background: impl Into<Background>) {}
+
+        fn clear(&mut self) {}
+    }
+
+    impl text::Renderer for TestRenderer {
+        type Font = Font;
+
+        const ICON_FONT: Font = Font::Default;
+        const CHECKMARK_ICON: char = '0';
+        const ARROW_DOWN_ICON: char = '0';
+
+        fn default_size(&self) -> f32 {
+            20.0
+        }
+
+        fn measure(&self, _content: &str, _size: f32, _font: Font, _bounds: Size) -> (f32, f32) {
+            (0.0, 20.0)
+        }
+
+        fn hit_test(
+            &self,
+            _contents: &str,
+            _size: f32,
+            _font: Font,
+            _bounds: Size,
+            _point: Point,
+            _nearest_only: bool,
+        ) -> Option<Hit> {
+            None
+        }
+
+        fn fill_text(&mut self, _text: text::Text<'_, Font>) {}
+    }
+
+    impl svg::Renderer for TestRenderer {
+        fn dimensions(&self, _handle: &svg::Handle) -> Size<u32> {
+            Size::new(0, 0)
+        }
+
+        fn draw(&mut self, _handle: svg::Handle, _color: Option<Color>, _bounds: Rectangle) {}
+    }
+
+    #[derive(Debug, Clone)]
+    enum Message {
+        Moved(MoveEvent),
+    }
+
+    /// The board as `view` would build it, anew for every message.
+    fn view<'a>() -> ChessBoard<'a, Message, TestRenderer> {
+        ChessBoard::new(SIZE)
+            .controlled(true)
+            .on_move(Message::Moved)
+    }
+
+    fn square_center(square: SQ) -> Point {
+        let cells_size = SIZE as f32 * 0.111;
+        let col = square.file_idx_of_sq() as f32;
+        let row = 7.0 - square.rank_idx_of_sq() as f32;
+        Point::new(cells_size * (col + 1.0), cells_size * (row + 1.0))
+    }
+
+    fn send(
+        board: &mut ChessBoard<'_, Message, TestRenderer>,
+        tree: &mut Tree,
+        event: mouse::Event,
+    ) -> Vec<Message> {
+        let node = layout::Node::new(Size::new(SIZE as f32, SIZE as f32));
+        let mut messages = vec![];
+        let mut shell = Shell::new(&mut messages);
+        Widget::on_event(
+            board,
+            tree,
+            Event::Mouse(event),
+            Layout::new(&node),
+            Point::ORIGIN,
+            &TestRenderer,
+            &mut clipboard::Null,
+            &mut shell,
+        );
+        messages
+    }
+
+    fn move_to(square: SQ) -> mouse::Event {
+        mouse::Event::CursorMoved {
+            position: square_center(square),
+        }
+    }
+
+    #[test]
+    fn drag_survives_the_board_being_rebuilt() {
+        let mut board = view();
+        let mut tree = Tree::new(&board as &dyn Widget<Message, TestRenderer>);
+        send(&mut board, &mut tree, move_to(SQ::E2));
+        send(
+            &mut board,
+            &mut tree,
+            mouse::Event::ButtonPressed(mouse::Button::Left),
+        );
+        send(&mut board, &mut tree, move_to(SQ::E3));
+
+        // A clock tick arrives: the application rebuilds the board from `view`.
+        let mut board = view();
+        tree.diff(&board as &dyn Widget<Message, TestRenderer>);
+
+        send(&mut board, &mut tree, move_to(SQ::E4));
+        let messages = send(
+            &mut board,
+            &mut tree,
+            mouse::Event::ButtonReleased(mouse::Button::Left),
+        );
+        match messages.as_slice() {
+            [Message::Moved(move_event)] => assert_eq!(move_event.uci, "e2e4"),
+            messages => panic!("expected one move, got {:?}", messages),
+        }
+    }
+
+    #[test]
+    fn releasing_without_a_drag_plays_nothing() {
+        let mut board = view();
+        let mut tree = Tree::new(&board as &dyn Widget<Message, TestRenderer>);
+        send(&mut board, &mut tree, move_to(SQ::E4));
+        let messages = send(
+            &mut board,
+            &mut tree,
+            mouse::Event::ButtonReleased(mouse::Button::Left),
+        );
+        assert!(messages.is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+use std::marker::PhantomData;
+
+use iced_native::renderer;
+use pleco::{Piece, SQ};
+
+use crate::game::{GameStatus, MoveEvent};
+use crate::moves::Moves;
+use crate::style::StyleSheet;
+
+use super::utils::Utils;
+use super::{ChessBoard, DragAndDropData, State};
+
+pub struct MouseHandler<Message, Renderer> {
+    _renderer: PhantomData<Renderer>,
+    _msg: PhantomData<Message>,
+}
+
+impl<Message, Renderer> MouseHandler<Message, Renderer>
+where
+    Renderer: renderer::Renderer,
+    Renderer::Theme: StyleSheet,
+{
+    pub fn handle_left_button_pressed(board: &ChessBoard<Message, Renderer>, state: &mut State) {
+        let accepts_moves = board.on_new_position.is_some() || board.on_move.is_some();
+        if state.drag_and_drop_data.is_none() && accepts_moves {
+            if let Some((start_file, start_rank)) = MouseHandler::hovered_cell(board, state) {
+                let pleco_file = Utils::coord_file_to_pleco_file(start_file as i32);
+                let pleco_rank = Utils::coord_rank_to_pleco_rank(start_rank as i32);
+                let square = SQ::make(pleco_file, pleco_rank);
+                let moved_piece = board.game.piece_at_sq(square);
+                let movable = moved_piece.player().is_some_and(|player| {
+                    board.movable.allows(player)
+                        && GameStatus::of(&board.game) == GameStatus::Ongoing
+                });
+
+                if moved_piece != Piece::None && movable {
+                    state.drag_and_drop_data = Some(DragAndDropData {
+                        start_file,
+                        start_rank,
+                        end_file: start_file,
+                        end_rank: start_rank,
+                        moved_piece,
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn handle_setup_button_pressed(
+        board: &ChessBoard<Message, Renderer>,
+        state: &State,
+        shell: &mut iced_native::Shell<'_, Message>,
+    ) {
+        if let Some((file, rank)) = MouseHandler::hovered_cell(board, state) {
+            let pleco_file = Utils::coord_file_to_pleco_file(file as i32);
+            let pleco_rank = Utils::coord_rank_to_pleco_rank(rank as i32);
+            if let Some(ref on_setup_cell_pressed) = board.on_setup_cell_pressed {
+                let message = (on_setup_cell_pressed)(SQ::make(pleco_file, pleco_rank));
+                shell.publish(message);
+            }
+        }
+    }
+
+    pub fn handle_left_button_released(
+        board: &mut ChessBoard<Message, Renderer>,
+        state: &mut State,
+        shell: &mut iced_native::Shell<'_, Message>,
+    ) {
+        if state.drag_and_drop_data.is_some() {
+            let dnd_data = state.drag_and_drop_data.clone().unwrap();
+            let end_file = dnd_data.end_file;
+            let end_rank = dnd_data.end_rank;
+
+            let end_square_in_cell_bounds =
+                (0..=7).contains(&end_file) && (0..=7).contains(&end_rank);
+            if end_square_in_cell_bounds {
+                let pleco_start_file = Utils::coord_file_to_pleco_file(dnd_data.start_file as i32);
+                let pleco_start_rank = Utils::coord_rank_to_pleco_rank(dnd_data.start_rank as i32);
+                let start_square = SQ::make(pleco_start_file, pleco_start_rank);
+
+                let pleco_end_file = Utils::coord_file_to_pleco_file(dnd_data.end_file as i32);
+                let pleco_end_rank = Utils::coord_rank_to_pleco_rank(dnd_data.end_rank as i32);
+                let end_square = SQ::make(pleco_end_file, pleco_end_rank);
+
+                let castling = board.chess960_castling.as_ref();
+                let played =
+                    Moves::play_square_move(&board.game, castling, start_square, end_square)
+                        .filter(|_| board.movable.allows(board.game.turn()));
+                if let Some((new_game, new_castling)) = played {
+                    let move_event =
+                        MoveEvent::between(&board.game, castling, &new_game, new_castling.as_ref());
+                    if !board.controlled {
+                        board.game = new_game;
+                        board.chess960_castling = new_castling;
+                    }
+                    if let Some(move_event) = move_event {
+                        MouseHandler::publish_move(board, move_event, shell);
+                    }
+                }
+            }
+
+            state.drag_and_drop_data = None;
+        }
+    }
+
+    pub fn handle_mouse_moved(board: &ChessBoard<Message, Renderer>, state: &mut State) {
+        let cells_size = (board.size as f32) * 0.111;
+        if state.drag_and_drop_data.is_some() {
+            let x = state.mouse_x;
+            let y = state.mouse_y;
+
+            let cell_col = ((x - cells_size * 0.5f32) / cells_size) as i32;
+            let cell_row = ((y - cells_size * 0.5f32) / cells_size) as i32;
+
+            let end_file = (if board.reversed {
+                7 - cell_col
+            } else {
+                cell_col
+            }) as i8;
+            let end_rank = (if board.reversed {
+                cell_row
+            } else {
+                7 - cell_row
+            }) as i8;
+
+            let mut dnd_data = state.drag_and_drop_data.clone().unwrap();
+
+            dnd_data.end_file = end_file;
+            dnd_data.end_rank = end_rank;
+
+            state.drag_and_drop_data = Some(dnd_data);
+        }
+    }
+
+    fn publish_move(
+        board: &ChessBoard<Message, Renderer>,
+        move_event: MoveEvent,
+        shell: &mut iced_native::Shell<'_, Message>,
+    ) {
+        if let Some(ref on_new_position) = board.on_new_position {
+            let message = (on_new_position)(move_event.fen.clone());
+            shell.publish(message);
+        }
+        if let Some(ref on_move) = board.on_move {
+            let message = (on_move)(move_event);
+            shell.publish(message);
+        }
+    }
+
+    fn hovered_cell(board: &ChessBoard<Message, Renderer>, state: &State) -> Option<(i8, i8)> {
+        let cells_size = (board.size as f32) * 0.111;
+        let x = state.mouse_x;
+        let y = state.mouse_y;
+
+        let cell_col = ((x - cells_size * 0.5f32) / cells_size) as i32;
+        let cell_row = ((y - cells_size * 0.5f32) / cells_size) as i32;
+
+        let in_cell_bounds = (0..=7).contains(&cell_col) && (0..=7).contains(&cell_row);
+        if !in_cell_bounds {
+            return None;
+        }
+
+        let cell_col = cell_col as i8;
+        let cell_row = cell_row as i8;
+
+        let file = if board.reversed {
+            7 - cell_col
+        } else {
+            cell_col
+        };
+        let rank = if board.reversed {
+            cell_row
+        } else {
+            7 - cell_row
+        };
+
+        Some((file, rank))
+    }
+}
@@ -0,0 +1,101 @@
+use iced_native::svg;
+use iced_native::svg::Handle;
+
+use pleco::Piece;
+
+/// The SVG images of the pieces, also handy to draw pieces outside of the board.
+#[derive(Clone)]
+pub struct PiecesVectors {
+    pub svg_wp: Handle,
+    pub svg_wn: Handle,
+    pub svg_wb: Handle,
+    pub svg_wr: Handle,
+    pub svg_wq: Handle,
+    pub svg_wk: Handle,
+    pub svg_bp: Handle,
+    pub svg_bn: Handle,
+    pub svg_bb: Handle,
+    pub svg_br: Handle,
+    pub svg_bq: Handle,
+    pub svg_bk: Handle,
+}
+
+impl PiecesVectors {
+    pub fn new() -> Self {
+        let svg_wp = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_plt45.svg")[..],
+        );
+        let svg_wn = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_nlt45.svg")[..],
+        );
+        let svg_wb = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_blt45.svg")[..],
+        );
+        let svg_wr = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_rlt45.svg")[..],
+        );
+        let svg_wq = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_qlt45.svg")[..],
+        );
+        let svg_wk = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_klt45.svg")[..],
+        );
+        let svg_bp = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_pdt45.svg")[..],
+        );
+        let svg_bn = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_ndt45.svg")[..],
+        );
+        let svg_bb = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_bdt45.svg")[..],
+        );
+        let svg_br = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_rdt45.svg")[..],
+        );
+        let svg_bq = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_qdt45.svg")[..],
+        );
+        let svg_bk = svg::Handle::from_memory(
+            &include_bytes!("../../resources/images/chess_vectors/Chess_kdt45.svg")[..],
+        );
+
+        Self {
+            svg_wp,
+            svg_wn,
+            svg_wb,
+            svg_wr,
+            svg_wq,
+            svg_wk,
+            svg_bp,
+            svg_bn,
+            svg_bb,
+            svg_br,
+            svg_bq,
+            svg_bk,
+        }
+    }
+
+    pub fn piece_handle(&self, piece: Piece) -> Option<Handle> {
+        match piece {
+            Piece::None => None,
+            Piece::WhitePawn => Some(self.svg_wp.clone()),
+            Piece::WhiteKnight => Some(self.svg_wn.clone()),
+            Piece::WhiteBishop => Some(self.svg_wb.clone()),
+            Piece::WhiteRook => Some(self.svg_wr.clone()),
+            Piece::WhiteQueen => Some(self.svg_wq.clone()),
+            Piece::WhiteKing => Some(self.svg_wk.clone()),
+            Piece::BlackPawn => Some(self.svg_bp.clone()),
+            Piece::BlackKnight => Some(self.svg_bn.clone()),
+            Piece::BlackBishop => Some(self.svg_bb.clone()),
+            Piece::BlackRook => Some(self.svg_br.clone()),
+            Piece::BlackQueen => Some(self.svg_bq.clone()),
+            Piece::BlackKing => Some(self.svg_bk.clone()),
+        }
+    }
+}
+
+impl Default for PiecesVectors {
+    fn default() -> Self {
+        PiecesVectors::new()
+    }
+}
@@ -4,7 +4,7 @@ pub struct Utils {}
 
 impl Utils {
     pub fn coord_file_to_pleco_file(input: i32) -> File {
-        let constrained_input = input.max(0).min(7);
+        let constrained_input = input.clamp(0, 7);
         match constrained_input {
             0 => File::A,
             1 => File::B,
@@ -19,7 +19,7 @@ impl Utils {
     }
 
     pub fn coord_rank_to_pleco_rank(input: i32) -> Rank {
-        let constrained_input = input.max(0).min(7);
+        let constrained_input = input.clamp(0, 7);
         match constrained_input {
             0 => Rank::R1,
             1 => Rank::R2,
@@ -32,4 +32,4 @@ impl Utils {
             _ => Rank::R1,
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,249 @@
+use pleco::{Board, Piece, PieceType, Player, SQ};
+
+use crate::chess960::{Chess960, Chess960Castling};
+use crate::moves::Moves;
+
+/// Whether a position ends the game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameStatus {
+    Ongoing,
+    Checkmate { winner: Player },
+    Stalemate,
+}
+
+impl GameStatus {
+    pub fn of(board: &Board) -> Self {
+        if board.checkmate() {
+            GameStatus::Checkmate {
+                winner: !board.turn(),
+            }
+        } else if board.stalemate() {
+            GameStatus::Stalemate
+        } else {
+            GameStatus::Ongoing
+        }
+    }
+
+    /// The result as written in PGN.
+    pub fn result(self) -> &'static str {
+        match self {
+            GameStatus::Ongoing => "*",
+            GameStatus::Checkmate {
+                winner: Player::White,
+            } => "1-0",
+            GameStatus::Checkmate {
+                winner: Player::Black,
+            } => "0-1",
+            GameStatus::Stalemate => "1/2-1/2",
+        }
+    }
+}
+
+/// The sides whose pieces the local player may move.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MovableSides {
+    Both,
+    Only(Player),
+    Neither,
+}
+
+impl MovableSides {
+    pub fn allows(self, player: Player) -> bool {
+        match self {
+            MovableSides::Both => true,
+            MovableSides::Only(allowed) => allowed == player,
+            MovableSides::Neither => false,
+        }
+    }
+}
+
+/// A move played on the board. Chess960 castling goes from the king to the rook.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveEvent {
+    pub player: Player,
+    pub from: SQ,
+    pub to: SQ,
+    pub promotion: Option<PieceType>,
+    pub captured: Option<Piece>,
+    pub san: String,
+    pub uci: String,
+    pub fen: String,
+}
+
+impl MoveEvent {
+    /// Describes the move leading from `previous` to `new_board`, if a legal one does.
+    pub fn between(
+        previous: &Board,
+        chess960_castling: Option<&Chess960Castling>,
+        new_board: &Board,
+        new_castling: Option<&Chess960Castling>,
+    ) -> Option<Self> {
+        let (san, uci) = Moves::identify_move(previous, chess960_castling, new_board)?;
+        let fen = match new_castling {
+            Some(castling) => Chess960::fen(new_board, castling),
+            None => new_board.fen(),
+        };
+        let from = Moves::parse_square(uci.get(0..2)?)?;
+        let to = Moves::parse_square(uci.get(2..4)?)?;
+        let promotion = match uci.get(4..5) {
+            Some("q") => Some(PieceType::Q),
+            Some("r") => Some(PieceType::R),
+            Some("b") => Some(PieceType::B),
+            Some("n") => Some(PieceType::N),
+            _ => None,
+        };
+        Some(Self {
+            player: previous.turn(),
+            from,
+            to,
+            promotion,
+            captured: MoveEvent::captured_piece(previous, from, to),
+            san,
+            uci,
+            fen,
+        })
+    }
+
+    fn captured_piece(previous: &Board, from: SQ, to: SQ) -> Option<Piece> {
+        let opponent = !previous.turn();
+        let target = previous.piece_at_sq(to);
+        if target.player() == Some(opponent) {
+            return Some(target);
+        }
+        let en_passant = previous.piece_at_sq(from).type_of() == PieceType::P
+            && from.file() != to.file()
+            && target == Piece::None;
+        en_passant.then(|| Piece::make_lossy(opponent, PieceType::P))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(fen: &str, uci_move: &str) -> MoveEvent {
+        let (board, castling) = Chess960::any_position_from_fen(fen).unwrap();
+        let (new_board, new_castling) =
+            Moves::play_uci_move(&board, castling.as_ref(), uci_move).unwrap();
+        MoveEvent::between(&board, castling.as_ref(), &new_board, new_castling.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn recognises_the_end_of_the_game() {
+        let status = |fen: &str| GameStatus::of(&Board::from_fen(fen).unwrap());
+        let ongoing = status("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(ongoing, GameStatus::Ongoing);
+        assert_eq!(ongoing.result(), "*");
+
+        let fools_mate = status("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+        assert_eq!(
+            fools_mate,
+            GameStatus::Checkmate {
+                winner: Player::Black
+            }
+        );
+        assert_eq!(fools_mate.result(), "0-1");
+
+        let back_rank = status("3R2k1/5ppp/8/8/8/8/8/6K1 b - - 1 1");
+        assert_eq!(back_rank.result(), "1-0");
+
+        let stalemate = status("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+        assert_eq!(stalemate, GameStatus::Stalemate);
+        assert_eq!(stalemate.result(), "1/2-1/2");
+
+        let check = status("4k3/8/8/8/8/8/8/4RK2 b - - 0 1");
+        assert_eq!(check, GameStatus::Ongoing);
+    }
+
+    #[test]
+    fn movable_sides_allow_the_right_players() {
+        assert!(MovableSides::Both.allows(Player::White));
+        assert!(MovableSides::Both.allows(Player::Black));
+        assert!(MovableSides::Only(Player::Black).allows(Player::Black));
+        assert!(!MovableSides::Only(Player::Black).allows(Player::White));
+        assert!(!MovableSides::Neither.allows(Player::White));
+    }
+
+    #[test]
+    fn describes_a_quiet_move() {
+        let event = event(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "g1f3",
+        );
+        assert_eq!(event.player, Player::White);
+        assert_eq!((event.from, event.to), (SQ::G1, SQ::F3));
+        assert_eq!(event.captured, None);
+        assert_eq!(event.promotion, None);
+        assert_eq!((event.san.as_str(), event.uci.as_str()), ("Nf3", "g1f3"));
+        assert_eq!(
+            event.fen,
+            "rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 1 1"
+        );
+    }
+
+    #[test]
+    fn reports_the_captured_piece() {
+        let event = event("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5");
+        assert_eq!(event.captured, Some(Piece::BlackPawn));
+        assert_eq!(event.san, "exd5");
+        assert_eq!(event.fen, "4k3/8/8/3P4/8/8/8/4K3 b - - 0 1");
+    }
+
+    #[test]
+    fn reports_the_pawn_taken_en_passant() {
+        let event = event("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2", "e5d6");
+        assert_eq!(event.captured, Some(Piece::BlackPawn));
+        assert_eq!((event.from, event.to), (SQ::E5, SQ::D6));
+        assert_eq!(event.san, "exd6");
+        assert_eq!(event.fen, "4k3/8/3P4/8/8/8/8/4K3 b - - 0 2");
+    }
+
+    #[test]
+    fn reports_promotions() {
+        let queen = event("8/4P3/8/8/8/8/k7/4K3 w - - 0 1", "e7e8q");
+        assert_eq!(queen.promotion, Some(PieceType::Q));
+        assert_eq!(queen.captured, None);
+        assert_eq!((queen.san.as_str(), queen.uci.as_str()), ("e8=Q", "e7e8q"));
+
+        let knight = event("3r4/4P3/8/8/8/8/k7/4K3 w - - 0 1", "e7d8n");
+        assert_eq!(knight.promotion, Some(PieceType::N));
+        assert_eq!(knight.captured, Some(Piece::BlackRook));
+        assert_eq!(knight.san, "exd8=N");
+    }
+
+    #[test]
+    fn describes_castling() {
+        let event = event("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", "e8c8");
+        assert_eq!(event.player, Player::Black);
+        assert_eq!((event.from, event.to), (SQ::E8, SQ::C8));
+        assert_eq!(event.captured, None);
+        assert_eq!(event.san, "O-O-O");
+        assert_eq!(event.fen, "2kr3r/8/8/8/8/8/8/R3K2R w KQ - 1 2");
+    }
+
+    #[test]
+    fn describes_chess960_castling_as_the_king_taking_its_rook() {
+        let event = event("4k3/8/8/8/8/8/8/1R3KR1 w GB - 0 1", "f1g1");
+        assert_eq!((event.from, event.to), (SQ::F1, SQ::G1));
+        assert_eq!(event.captured, None);
+        assert_eq!((event.san.as_str(), event.uci.as_str()), ("O-O", "f1g1"));
+        assert_eq!(event.fen, "4k3/8/8/8/8/8/8/1R3RK1 b - - 1 1");
+    }
+
+    #[test]
+    fn needs_a_legal_move_between_the_positions() {
+        let (board, _) = Chess960::any_position_from_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        )
+        .unwrap();
+        let (two_moves_later, _) = Chess960::any_position_from_fen(
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2",
+        )
+        .unwrap();
+        assert_eq!(MoveEvent::between(&board, None, &board, None), None);
+        assert_eq!(
+            MoveEvent::between(&board, None, &two_moves_later, None),
+            None
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! A chess board widget for [iced](https://github.com/iced-rs/iced).
+//!
+//! The board lets the user drag pieces to play legal moves, including Chess960 castling, and
+//! reports every move through callbacks. It can also show a position being set up, a hint
+//! arrow and an annotation glyph.
+//!
+//! ```no_run
+//! use iced::Element;
+//! use iced_chessboard::{ChessBoard, MoveEvent};
+//! use pleco::Board;
+//!
+//! #[derive(Debug, Clone)]
+//! enum Message {
+//!     Moved(MoveEvent),
+//! }
+//!
+//! fn view(game: &Board) -> Element<'_, Message> {
+//!     ChessBoard::new(400)
+//!         .game(game.clone())
+//!         .controlled(true)
+//!         .on_move(Message::Moved)
+//!         .into()
+//! }
+//! ```
+//!
+//! In controlled mode, as above, the board only proposes moves and keeps showing the position
+//! it was given until the application passes the next one. Otherwise it plays the move itself
+//! and the callbacks only inform the application.
+//!
+//! The board takes its colors from the theme through [`style::StyleSheet`]. With `iced::Theme`
+//! it switches between a light and a dark appearance, and `.style(appearance)` fixes the
+//! colors instead.
+//!
+//! The rules helpers the widget is built on, such as Chess960 castling, FEN validation and
+//! SAN, are public too so that applications can share them.
+
+pub mod chess960;
+pub mod position_setup;
+pub mod san;
+pub mod style;
+
+mod chessboard;
+mod game;
+mod moves;
+
+pub use chessboard::{ChessBoard, PiecesVectors};
+pub use game::{GameStatus, MovableSides, MoveEvent};
+pub use moves::Moves;
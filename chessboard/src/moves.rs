@@ -0,0 +1,230 @@
+use pleco::core::CastleType;
+use pleco::{Board, SQ};
+
+use crate::chess960::Chess960Castling;
+use crate::position_setup::CastlingRight;
+use crate::san::San;
+
+/// Legal move helpers working on a `pleco::Board` and, for Chess960, its castling rights.
+pub struct Moves {}
+
+impl Moves {
+    /// Finds the move leading from `previous` to `new_board` and returns it in SAN and UCI
+    /// notation. Chess960 castling is written in UCI as the king taking its own rook.
+    pub fn identify_move(
+        previous: &Board,
+        chess960_castling: Option<&Chess960Castling>,
+        new_board: &Board,
+    ) -> Option<(String, String)> {
+        let new_position = Moves::position_key(&new_board.fen());
+
+        let ordinary_move = previous.generate_moves().iter().find_map(|pleco_move| {
+            let mut candidate = previous.clone();
+            candidate.apply_move(*pleco_move);
+            if Moves::position_key(&candidate.fen()) == new_position {
+                Some((
+                    San::from_move(previous, *pleco_move),
+                    pleco_move.stringify(),
+                ))
+            } else {
+                None
+            }
+        });
+        if ordinary_move.is_some() {
+            return ordinary_move;
+        }
+
+        let chess960_castling = chess960_castling?;
+        CastlingRight::ALL
+            .into_iter()
+            .filter(|right| right.player() == previous.turn())
+            .find_map(|right| {
+                let candidate = chess960_castling.castle(previous, right)?;
+                if Moves::position_key(&candidate.fen()) != new_position {
+                    return None;
+                }
+                let king_square = previous.king_sq(previous.turn());
+                let rook_square = chess960_castling.rook_square(right)?;
+                Some((
+                    San::castling(right.castle_type(), &candidate),
+                    format!("{}{}", king_square, rook_square),
+                ))
+            })
+    }
+
+    /// Plays a move given in UCI notation and returns the new position and castling rights.
+    pub fn play_uci_move(
+        board: &Board,
+        chess960_castling: Option<&Chess960Castling>,
+        uci_move: &str,
+    ) -> Option<(Board, Option<Chess960Castling>)> {
+        let pleco_move = board
+            .generate_moves()
+            .iter()
+            .find(|pleco_move| pleco_move.stringify() == uci_move)
+            .cloned();
+
+        if let Some(chess960_castling) = chess960_castling {
+            let src = Moves::parse_square(uci_move.get(0..2)?)?;
+            let dest = Moves::parse_square(uci_move.get(2..4)?)?;
+            if let Some((_, new_board)) =
+                chess960_castling.castle_for_drag(board, src, dest, pleco_move.is_some())
+            {
+                return Some((
+                    new_board,
+                    Some(chess960_castling.after_castling(board.turn())),
+                ));
+            }
+            let pleco_move = pleco_move?;
+            let new_castling = chess960_castling.after_move(board, src, dest);
+            let mut new_board = board.clone();
+            new_board.apply_move(pleco_move);
+            return Some((new_board, Some(new_castling)));
+        }
+
+        let mut new_board = board.clone();
+        new_board.apply_move(pleco_move?);
+        Some((new_board, None))
+    }
+
+    /// Plays the move of the piece on `src` to `dest`, as entered on a board. In Chess960 the
+    /// king may also be dropped on its own rook or on its castling square to castle.
+    pub fn play_square_move(
+        board: &Board,
+        chess960_castling: Option<&Chess960Castling>,
+        src: SQ,
+        dest: SQ,
+    ) -> Option<(Board, Option<Chess960Castling>)> {
+        let pleco_move = board
+            .generate_moves()
+            .iter()
+            .rev()
+            .find(|pleco_move| {
+                pleco_move.get_src() == src
+                    && (pleco_move.get_dest() == dest
+                        || pleco_move.stringify()[2..4] == dest.to_string())
+            })
+            .cloned();
+
+        let chess960_castle = chess960_castling.and_then(|castling| {
+            castling
+                .castle_for_drag(board, src, dest, pleco_move.is_some())
+                .map(|(_, new_board)| (new_board, Some(castling.after_castling(board.turn()))))
+        });
+        if chess960_castle.is_some() {
+            return chess960_castle;
+        }
+        let pleco_move = pleco_move?;
+        let new_castling = chess960_castling.map(|castling| castling.after_move(board, src, dest));
+        let mut new_board = board.clone();
+        new_board.apply_move(pleco_move);
+        Some((new_board, new_castling))
+    }
+
+    /// Plays a move given in SAN. "O-O" and "O-O-O" also castle in Chess960.
+    pub fn play_san_move(
+        board: &Board,
+        chess960_castling: Option<&Chess960Castling>,
+        san: &str,
+    ) -> Option<(Board, Option<Chess960Castling>)> {
+        if let Some(chess960_castling) = chess960_castling {
+            let castle_type = match san.replace('0', "O").trim_end_matches(['+', '#']) {
+                "O-O" => Some(CastleType::KingSide),
+                "O-O-O" => Some(CastleType::QueenSide),
+                _ => None,
+            };
+            if let Some(castle_type) = castle_type {
+                let right = CastlingRight::ALL.into_iter().find(|right| {
+                    right.player() == board.turn() && right.castle_type() == castle_type
+                })?;
+                let new_board = chess960_castling.castle(board, right)?;
+                return Some((
+                    new_board,
+                    Some(chess960_castling.after_castling(board.turn())),
+                ));
+            }
+        }
+        let pleco_move = San::to_move(board, san)?;
+        Moves::play_uci_move(board, chess960_castling, &pleco_move.stringify())
+    }
+
+    fn position_key(fen: &str) -> String {
+        fen.split_whitespace().take(2).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Reads a square such as "e4".
+    pub fn parse_square(square: &str) -> Option<SQ> {
+        let mut chars = square.chars();
+        let file = chars.next().filter(|file| ('a'..='h').contains(file))?;
+        let rank = chars.next().filter(|rank| ('1'..='8').contains(rank))?;
+        Some(SQ((rank as u8 - b'1') * 8 + (file as u8 - b'a')))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::chess960::Chess960;
+
+    #[test]
+    fn parses_squares() {
+        assert_eq!(Moves::parse_square("a1"), Some(SQ::A1));
+        assert_eq!(Moves::parse_square("e4"), Some(SQ::E4));
+        assert_eq!(Moves::parse_square("h8"), Some(SQ::H8));
+        assert_eq!(Moves::parse_square("i1"), None);
+        assert_eq!(Moves::parse_square("e9"), None);
+        assert_eq!(Moves::parse_square("e"), None);
+    }
+
+    #[test]
+    fn plays_moves_in_every_notation() {
+        let start = Board::start_pos();
+        let (by_uci, _) = Moves::play_uci_move(&start, None, "g1f3").unwrap();
+        let (by_san, _) = Moves::play_san_move(&start, None, "Nf3").unwrap();
+        let (by_squares, _) = Moves::play_square_move(&start, None, SQ::G1, SQ::F3).unwrap();
+        assert_eq!(by_uci.fen(), by_san.fen());
+        assert_eq!(by_uci.fen(), by_squares.fen());
+        assert_eq!(Moves::play_uci_move(&start, None, "g1g3"), None);
+        assert_eq!(Moves::play_san_move(&start, None, "Ng3"), None);
+        assert_eq!(Moves::play_square_move(&start, None, SQ::E2, SQ::E5), None);
+    }
+
+    #[test]
+    fn a_pawn_dropped_on_the_last_rank_becomes_a_queen() {
+        let board = Board::from_fen("8/4P3/8/8/8/8/k7/4K3 w - - 0 1").unwrap();
+        let (promoted, _) = Moves::play_square_move(&board, None, SQ::E7, SQ::E8).unwrap();
+        assert_eq!(promoted.fen(), "4Q3/8/8/8/8/8/k7/4K3 b - - 0 1");
+        let (underpromoted, _) = Moves::play_uci_move(&board, None, "e7e8n").unwrap();
+        assert_eq!(underpromoted.fen(), "4N3/8/8/8/8/8/k7/4K3 b - - 0 1");
+    }
+
+    #[test]
+    fn identifies_the_move_between_two_positions() {
+        let start = Board::start_pos();
+        let (after, _) = Moves::play_uci_move(&start, None, "e2e4").unwrap();
+        assert_eq!(
+            Moves::identify_move(&start, None, &after),
+            Some((String::from("e4"), String::from("e2e4")))
+        );
+        assert_eq!(Moves::identify_move(&start, None, &start), None);
+    }
+
+    #[test]
+    fn chess960_castling_keeps_the_castling_rights_in_step() {
+        let (board, castling) =
+            Chess960::position_from_fen("r3k2r/8/8/8/8/8/8/1R2K2R w HBha - 0 1").unwrap();
+        let (castled, new_castling) = Moves::play_san_move(&board, Some(&castling), "O-O").unwrap();
+        let new_castling = new_castling.unwrap();
+        assert_eq!(
+            Chess960::fen(&castled, &new_castling),
+            "r3k2r/8/8/8/8/8/8/1R3RK1 b ha - 1 1"
+        );
+        let (rook_moved, rook_castling) =
+            Moves::play_uci_move(&board, Some(&castling), "b1b2").unwrap();
+        assert_eq!(
+            Chess960::fen(&rook_moved, &rook_castling.unwrap()),
+            "r3k2r/8/8/8/8/8/1R6/4K2R b Hha - 1 1"
+        );
+    }
+}
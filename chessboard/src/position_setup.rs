@@ -0,0 +1,602 @@
+use std::fmt;
+
+use pleco::core::sq::NO_SQ;
+use pleco::core::CastleType;
+use pleco::{Board, File, Piece, PieceType, Player, Rank, SQ};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastlingRight {
+    WhiteShort,
+    WhiteLong,
+    BlackShort,
+    BlackLong,
+}
+
+impl CastlingRight {
+    pub const ALL: [CastlingRight; 4] = [
+        CastlingRight::WhiteShort,
+        CastlingRight::WhiteLong,
+        CastlingRight::BlackShort,
+        CastlingRight::BlackLong,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn player(self) -> Player {
+        match self {
+            CastlingRight::WhiteShort | CastlingRight::WhiteLong => Player::White,
+            CastlingRight::BlackShort | CastlingRight::BlackLong => Player::Black,
+        }
+    }
+
+    pub fn castle_type(self) -> CastleType {
+        match self {
+            CastlingRight::WhiteShort | CastlingRight::BlackShort => CastleType::KingSide,
+            CastlingRight::WhiteLong | CastlingRight::BlackLong => CastleType::QueenSide,
+        }
+    }
+
+    fn fen_char(self) -> char {
+        match self {
+            CastlingRight::WhiteShort => 'K',
+            CastlingRight::WhiteLong => 'Q',
+            CastlingRight::BlackShort => 'k',
+            CastlingRight::BlackLong => 'q',
+        }
+    }
+
+    fn rook_square(self) -> SQ {
+        match self {
+            CastlingRight::WhiteShort => SQ::H1,
+            CastlingRight::WhiteLong => SQ::A1,
+            CastlingRight::BlackShort => SQ::H8,
+            CastlingRight::BlackLong => SQ::A8,
+        }
+    }
+}
+
+impl fmt::Display for CastlingRight {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CastlingRight::WhiteShort => write!(f, "White O-O"),
+            CastlingRight::WhiteLong => write!(f, "White O-O-O"),
+            CastlingRight::BlackShort => write!(f, "Black O-O"),
+            CastlingRight::BlackLong => write!(f, "Black O-O-O"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetupError {
+    SectionsCount(usize),
+    RanksCount(usize),
+    RankLength(usize),
+    UnknownPiece(char),
+    InvalidTurn(String),
+    InvalidCastlingField(String),
+    InvalidEnPassantField(String),
+    InvalidMoveCounter(String),
+    MissingKing(Player),
+    TooManyKings(Player),
+    TooManyPieces(Player),
+    PawnOnBackRank(SQ),
+    CastlingWithoutKing(CastlingRight),
+    CastlingWithoutRook(CastlingRight),
+    InvalidEnPassant(SQ),
+    OpponentInCheck(Player),
+    Rejected(String),
+}
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SetupError::SectionsCount(count) => write!(
+                f,
+                "Expected 6 space separated fields (or at least 4), found {}",
+                count
+            ),
+            SetupError::RanksCount(count) => {
+                write!(f, "Expected 8 ranks separated by '/', found {}", count)
+            }
+            SetupError::RankLength(rank) => {
+                write!(f, "Rank {} does not describe exactly 8 squares", rank)
+            }
+            SetupError::UnknownPiece(piece) => write!(f, "Unknown piece '{}'", piece),
+            SetupError::InvalidTurn(turn) => {
+                write!(f, "Invalid side to move '{}', expected 'w' or 'b'", turn)
+            }
+            SetupError::InvalidCastlingField(castling) => write!(
+                f,
+                "Invalid castling field '{}', expected '-' or a combination of 'KQkq'",
+                castling
+            ),
+            SetupError::InvalidEnPassantField(en_passant) => write!(
+                f,
+                "Invalid en passant field '{}', expected '-' or a square on the 3rd or 6th rank",
+                en_passant
+            ),
+            SetupError::InvalidMoveCounter(counter) => {
+                write!(f, "Invalid move counter '{}'", counter)
+            }
+            SetupError::MissingKing(player) => write!(f, "{} has no king", player),
+            SetupError::TooManyKings(player) => write!(f, "{} has more than one king", player),
+            SetupError::TooManyPieces(player) => {
+                write!(f, "{} has more than 16 pieces", player)
+            }
+            SetupError::PawnOnBackRank(square) => {
+                write!(
+                    f,
+                    "Pawn on {} cannot stand on the first or last rank",
+                    square
+                )
+            }
+            SetupError::CastlingWithoutKing(right) => {
+                write!(f, "{} needs the king on its starting square", right)
+            }
+            SetupError::CastlingWithoutRook(right) => {
+                write!(f, "{} needs the rook on its starting square", right)
+            }
+            SetupError::InvalidEnPassant(square) => write!(
+                f,
+                "En passant on {} needs a pawn that has just moved two squares",
+                square
+            ),
+            SetupError::OpponentInCheck(player) => {
+                write!(f, "{} is in check but it is not their turn", player)
+            }
+            SetupError::Rejected(reason) => write!(f, "Rejected position: {}", reason),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PositionSetup {
+    pieces: [Piece; 64],
+    turn: Player,
+    castling: [bool; 4],
+    en_passant_file: Option<File>,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+}
+
+impl PositionSetup {
+    pub fn empty() -> Self {
+        Self {
+            pieces: [Piece::None; 64],
+            turn: Player::White,
+            castling: [false; 4],
+            en_passant_file: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+        }
+    }
+
+    pub fn from_fen(fen: &str) -> Result<Self, SetupError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() < 4 || fields.len() > 6 {
+            return Err(SetupError::SectionsCount(fields.len()));
+        }
+
+        let ranks: Vec<&str> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err(SetupError::RanksCount(ranks.len()));
+        }
+
+        let mut setup = Self::empty();
+        for (row, rank_str) in ranks.iter().enumerate() {
+            let rank = 7 - row;
+            let mut file = 0usize;
+            for piece_char in rank_str.chars() {
+                if let Some(empty_cells) = piece_char.to_digit(10) {
+                    if empty_cells == 0 || empty_cells > 8 {
+                        return Err(SetupError::RankLength(rank + 1));
+                    }
+                    file += empty_cells as usize;
+                } else {
+                    if file > 7 {
+                        return Err(SetupError::RankLength(rank + 1));
+                    }
+                    setup.pieces[rank * 8 + file] = PositionSetup::char_to_piece(piece_char)?;
+                    file += 1;
+                }
+            }
+            if file != 8 {
+                return Err(SetupError::RankLength(rank + 1));
+            }
+        }
+
+        setup.turn = match fields[1] {
+            "w" => Player::White,
+            "b" => Player::Black,
+            turn => return Err(SetupError::InvalidTurn(turn.to_string())),
+        };
+
+        if fields[2] != "-" {
+            for castling_char in fields[2].chars() {
+                let right = CastlingRight::ALL
+                    .into_iter()
+                    .find(|right| right.fen_char() == castling_char)
+                    .ok_or_else(|| SetupError::InvalidCastlingField(fields[2].to_string()))?;
+                if setup.can_castle(right) {
+                    return Err(SetupError::InvalidCastlingField(fields[2].to_string()));
+                }
+                setup.set_castling(right, true);
+            }
+        }
+
+        if fields[3] != "-" {
+            let expected_rank = if setup.turn == Player::White {
+                '6'
+            } else {
+                '3'
+            };
+            let mut en_passant_chars = fields[3].chars();
+            match (
+                en_passant_chars.next(),
+                en_passant_chars.next(),
+                en_passant_chars.next(),
+            ) {
+                (Some(file @ 'a'..='h'), Some(rank), None) if rank == expected_rank => {
+                    setup.en_passant_file = Some(SQ(file as u8 - b'a').file());
+                }
+                _ => return Err(SetupError::InvalidEnPassantField(fields[3].to_string())),
+            }
+        }
+
+        if let Some(halfmove_clock) = fields.get(4) {
+            setup.halfmove_clock = halfmove_clock
+                .parse()
+                .map_err(|_| SetupError::InvalidMoveCounter(halfmove_clock.to_string()))?;
+        }
+        if let Some(fullmove_number) = fields.get(5) {
+            setup.fullmove_number = fullmove_number
+                .parse()
+                .ok()
+                .filter(|number| *number > 0)
+                .ok_or_else(|| SetupError::InvalidMoveCounter(fullmove_number.to_string()))?;
+        }
+
+        Ok(setup)
+    }
+
+    pub fn board_from_fen(fen: &str) -> Result<Board, SetupError> {
+        PositionSetup::from_fen(fen)?.validate()
+    }
+
+    pub fn from_board(board: &Board) -> Self {
+        let mut setup = Self::empty();
+        (0..64u8).for_each(|index| {
+            setup.pieces[index as usize] = board.piece_at_sq(SQ(index));
+        });
+        setup.turn = board.turn();
+        CastlingRight::ALL.iter().for_each(|right| {
+            setup.castling[right.index()] = board.can_castle(right.player(), right.castle_type());
+        });
+        let ep_square = board.ep_square();
+        if ep_square != NO_SQ {
+            setup.en_passant_file = Some(ep_square.file());
+        }
+        setup.halfmove_clock = board.rule_50().max(0) as u16;
+        setup.fullmove_number = board.moves_played() / 2 + 1;
+        setup
+    }
+
+    pub fn piece_at_sq(&self, square: SQ) -> Piece {
+        self.pieces[square.0 as usize]
+    }
+
+    pub fn set_piece_at_sq(&mut self, square: SQ, piece: Piece) {
+        self.pieces[square.0 as usize] = piece;
+    }
+
+    pub fn turn(&self) -> Player {
+        self.turn
+    }
+
+    pub fn set_turn(&mut self, turn: Player) {
+        self.turn = turn;
+    }
+
+    pub fn can_castle(&self, right: CastlingRight) -> bool {
+        self.castling[right.index()]
+    }
+
+    pub fn set_castling(&mut self, right: CastlingRight, enabled: bool) {
+        self.castling[right.index()] = enabled;
+    }
+
+    pub fn en_passant_file(&self) -> Option<File> {
+        self.en_passant_file
+    }
+
+    pub fn set_en_passant_file(&mut self, file: Option<File>) {
+        self.en_passant_file = file;
+    }
+
+    pub fn halfmove_clock(&self) -> u16 {
+        self.halfmove_clock
+    }
+
+    pub fn fullmove_number(&self) -> u16 {
+        self.fullmove_number
+    }
+
+    pub fn set_move_counters(&mut self, halfmove_clock: u16, fullmove_number: u16) {
+        self.halfmove_clock = halfmove_clock;
+        self.fullmove_number = fullmove_number;
+    }
+
+    pub fn fen(&self) -> String {
+        let mut placement = String::new();
+        (0..8u8).rev().for_each(|rank| {
+            let mut empty_cells = 0;
+            (0..8u8).for_each(
+                |file| match self.pieces[(rank * 8 + file) as usize].character() {
+                    Some(piece_char) => {
+                        if empty_cells > 0 {
+                            placement.push_str(&empty_cells.to_string());
+                            empty_cells = 0;
+                        }
+                        placement.push(piece_char);
+                    }
+                    None => empty_cells += 1,
+                },
+            );
+            if empty_cells > 0 {
+                placement.push_str(&empty_cells.to_string());
+            }
+            if rank > 0 {
+                placement.push('/');
+            }
+        });
+
+        let turn = if self.turn == Player::White { "w" } else { "b" };
+
+        let castling: String = CastlingRight::ALL
+            .iter()
+            .filter(|right| self.can_castle(**right))
+            .map(|right| right.fen_char())
+            .collect();
+        let castling = if castling.is_empty() {
+            String::from("-")
+        } else {
+            castling
+        };
+
+        let en_passant = match self.en_passant_square() {
+            Some(square) => square.to_string(),
+            None => String::from("-"),
+        };
+
+        format!(
+            "{} {} {} {} {} {}",
+            placement, turn, castling, en_passant, self.halfmove_clock, self.fullmove_number
+        )
+    }
+
+    pub fn validate(&self) -> Result<Board, SetupError> {
+        for player in [Player::White, Player::Black] {
+            let king = Piece::make_lossy(player, PieceType::K);
+            let kings_count = self.pieces.iter().filter(|piece| **piece == king).count();
+            if kings_count == 0 {
+                return Err(SetupError::MissingKing(player));
+            }
+            if kings_count > 1 {
+                return Err(SetupError::TooManyKings(player));
+            }
+            let pieces_count = self
+                .pieces
+                .iter()
+                .filter(|piece| piece.player() == Some(player))
+                .count();
+            if pieces_count > 16 {
+                return Err(SetupError::TooManyPieces(player));
+            }
+        }
+
+        for index in (0..8u8).chain(56..64u8) {
+            if self.pieces[index as usize].type_of() == PieceType::P {
+                return Err(SetupError::PawnOnBackRank(SQ(index)));
+            }
+        }
+
+        for right in CastlingRight::ALL {
+            if !self.can_castle(right) {
+                continue;
+            }
+            let player = right.player();
+            let king_square = player.relative_square(SQ::E1);
+            if self.piece_at_sq(king_square) != Piece::make_lossy(player, PieceType::K) {
+                return Err(SetupError::CastlingWithoutKing(right));
+            }
+            if self.piece_at_sq(right.rook_square()) != Piece::make_lossy(player, PieceType::R) {
+                return Err(SetupError::CastlingWithoutRook(right));
+            }
+        }
+
+        if let Some(target_square) = self.en_passant_square() {
+            let opponent = self.turn.other_player();
+            let file = target_square.file();
+            let pushed_pawn_square = SQ::make(file, opponent.relative_rank(Rank::R4));
+            let origin_square = SQ::make(file, opponent.relative_rank(Rank::R2));
+            let pawn_has_just_moved = self.piece_at_sq(pushed_pawn_square)
+                == Piece::make_lossy(opponent, PieceType::P)
+                && self.piece_at_sq(target_square) == Piece::None
+                && self.piece_at_sq(origin_square) == Piece::None;
+            if !pawn_has_just_moved {
+                return Err(SetupError::InvalidEnPassant(target_square));
+            }
+        }
+
+        let board = Board::from_fen(&self.fen())
+            .map_err(|err| SetupError::Rejected(format!("{:?}", err).trim().to_string()))?;
+
+        let opponent = self.turn.other_player();
+        let opponent_attackers = board.attackers_to(board.king_sq(opponent), board.occupied())
+            & board.get_occupied_player(self.turn);
+        if opponent_attackers.is_not_empty() {
+            return Err(SetupError::OpponentInCheck(opponent));
+        }
+
+        Ok(board)
+    }
+
+    fn char_to_piece(piece_char: char) -> Result<Piece, SetupError> {
+        match piece_char {
+            'P' => Ok(Piece::WhitePawn),
+            'N' => Ok(Piece::WhiteKnight),
+            'B' => Ok(Piece::WhiteBishop),
+            'R' => Ok(Piece::WhiteRook),
+            'Q' => Ok(Piece::WhiteQueen),
+            'K' => Ok(Piece::WhiteKing),
+            'p' => Ok(Piece::BlackPawn),
+            'n' => Ok(Piece::BlackKnight),
+            'b' => Ok(Piece::BlackBishop),
+            'r' => Ok(Piece::BlackRook),
+            'q' => Ok(Piece::BlackQueen),
+            'k' => Ok(Piece::BlackKing),
+            _ => Err(SetupError::UnknownPiece(piece_char)),
+        }
+    }
+
+    fn en_passant_square(&self) -> Option<SQ> {
+        self.en_passant_file.map(|file| {
+            let rank = if self.turn == Player::White {
+                Rank::R6
+            } else {
+                Rank::R3
+            };
+            SQ::make(file, rank)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    #[test]
+    fn accepts_valid_positions() {
+        assert_eq!(
+            PositionSetup::board_from_fen(START_FEN).unwrap().fen(),
+            START_FEN
+        );
+        let after_e4 = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+        assert_eq!(
+            PositionSetup::board_from_fen(after_e4).unwrap().fen(),
+            after_e4
+        );
+        // The move counters are optional.
+        assert!(PositionSetup::board_from_fen("4k3/8/8/8/8/8/8/4K3 w - -").is_ok());
+    }
+
+    #[test]
+    fn reports_each_fen_error() {
+        let cases = [
+            ("4k3/8/8/8/8/8/8/4K3 w", SetupError::SectionsCount(2)),
+            ("4k3/8/8/8/8/8/4K3 w - - 0 1", SetupError::RanksCount(7)),
+            ("4k3/8/8/8/8/8/8/4K4 w - - 0 1", SetupError::RankLength(1)),
+            ("4k3/8/8/8/8/8/8/4K2 w - - 0 1", SetupError::RankLength(1)),
+            (
+                "4k3/8/8/8/8/8/8/4K2X w - - 0 1",
+                SetupError::UnknownPiece('X'),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 x - - 0 1",
+                SetupError::InvalidTurn(String::from("x")),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w KX - 0 1",
+                SetupError::InvalidCastlingField(String::from("KX")),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w KK - 0 1",
+                SetupError::InvalidCastlingField(String::from("KK")),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w - e3 0 1",
+                SetupError::InvalidEnPassantField(String::from("e3")),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w - - x 1",
+                SetupError::InvalidMoveCounter(String::from("x")),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w - - 0 0",
+                SetupError::InvalidMoveCounter(String::from("0")),
+            ),
+            (
+                "8/8/8/8/8/8/8/4K3 w - - 0 1",
+                SetupError::MissingKing(Player::Black),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4KK2 w - - 0 1",
+                SetupError::TooManyKings(Player::White),
+            ),
+            (
+                "4k3/8/8/8/8/QQQQQQQQ/QQQQQQQQ/QQQK4 w - - 0 1",
+                SetupError::TooManyPieces(Player::White),
+            ),
+            (
+                "4k2P/8/8/8/8/8/8/4K3 w - - 0 1",
+                SetupError::PawnOnBackRank(SQ::H8),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/p3K3 w - - 0 1",
+                SetupError::PawnOnBackRank(SQ::A1),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/3K3R w K - 0 1",
+                SetupError::CastlingWithoutKing(CastlingRight::WhiteShort),
+            ),
+            (
+                "4k2r/8/8/8/8/8/8/4K3 w q - 0 1",
+                SetupError::CastlingWithoutRook(CastlingRight::BlackLong),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w - e6 0 1",
+                SetupError::InvalidEnPassant(SQ::E6),
+            ),
+            (
+                "4k3/4Q3/8/8/8/8/8/4K3 w - - 0 1",
+                SetupError::OpponentInCheck(Player::Black),
+            ),
+        ];
+        for (fen, expected) in cases {
+            assert_eq!(
+                PositionSetup::board_from_fen(fen).map(|board| board.fen()),
+                Err(expected),
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn error_messages_name_the_problem() {
+        let message = |fen: &str| PositionSetup::board_from_fen(fen).unwrap_err().to_string();
+        assert_eq!(
+            message("4k3/8/8/8/8/8/8/4KK2 w - - 0 1"),
+            "White has more than one king"
+        );
+        assert_eq!(
+            message("4k2P/8/8/8/8/8/8/4K3 w - - 0 1"),
+            "Pawn on h8 cannot stand on the first or last rank"
+        );
+    }
+
+    #[test]
+    fn setup_round_trips_through_fen() {
+        let fen = "r3k2r/pp3ppp/8/3pP3/8/8/PP3PPP/R3K2R w Kq d6 0 12";
+        let setup = PositionSetup::from_fen(fen).unwrap();
+        assert_eq!(setup.fen(), fen);
+        assert_eq!(
+            PositionSetup::from_board(&setup.validate().unwrap()).fen(),
+            fen
+        );
+    }
+}
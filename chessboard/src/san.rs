@@ -0,0 +1,228 @@
+use pleco::core::CastleType;
+use pleco::{BitMove, Board, PieceType};
+
+pub struct San {}
+
+impl San {
+    pub fn from_move(board: &Board, pleco_move: BitMove) -> String {
+        let mut board_after = board.clone();
+        board_after.apply_move(pleco_move);
+
+        if pleco_move.is_castle() {
+            let castle_type = if pleco_move.is_king_castle() {
+                CastleType::KingSide
+            } else {
+                CastleType::QueenSide
+            };
+            return San::castling(castle_type, &board_after);
+        }
+
+        let src = pleco_move.get_src();
+        let dest = pleco_move.get_dest();
+        let piece_type = board.moved_piece(pleco_move).type_of();
+        let is_capture = board.is_capture(pleco_move);
+
+        let mut san = String::new();
+        if piece_type == PieceType::P {
+            if is_capture {
+                san.push(San::file_char(src.file_idx_of_sq()));
+            }
+        } else {
+            san.push(piece_type.char_upper());
+
+            let ambiguous_moves: Vec<BitMove> = board
+                .generate_moves()
+                .iter()
+                .filter(|other| {
+                    other.get_dest() == dest
+                        && other.get_src() != src
+                        && board.moved_piece(**other).type_of() == piece_type
+                })
+                .cloned()
+                .collect();
+            if !ambiguous_moves.is_empty() {
+                let same_file = ambiguous_moves
+                    .iter()
+                    .any(|other| other.get_src().file() == src.file());
+                let same_rank = ambiguous_moves
+                    .iter()
+                    .any(|other| other.get_src().rank() == src.rank());
+                if !same_file {
+                    san.push(San::file_char(src.file_idx_of_sq()));
+                } else if !same_rank {
+                    san.push(San::rank_char(src.rank_idx_of_sq()));
+                } else {
+                    san.push(San::file_char(src.file_idx_of_sq()));
+                    san.push(San::rank_char(src.rank_idx_of_sq()));
+                }
+            }
+        }
+
+        if is_capture {
+            san.push('x');
+        }
+        san.push_str(&dest.to_string());
+
+        if pleco_move.is_promo() {
+            san.push('=');
+            san.push(pleco_move.promo_piece().char_upper());
+        }
+
+        san.push_str(San::check_suffix(&board_after));
+        san
+    }
+
+    pub fn from_uci_line(board: &Board, uci_moves: &[String]) -> Vec<String> {
+        let mut board = board.clone();
+        let mut line = vec![];
+        for uci_move in uci_moves {
+            let pleco_move = board
+                .generate_moves()
+                .iter()
+                .find(|pleco_move| pleco_move.stringify() == *uci_move)
+                .cloned();
+            match pleco_move {
+                Some(pleco_move) => {
+                    line.push(San::from_move(&board, pleco_move));
+                    board.apply_move(pleco_move);
+                }
+                None => {
+                    line.push(uci_move.clone());
+                    break;
+                }
+            }
+        }
+        line
+    }
+
+    pub fn to_move(board: &Board, san: &str) -> Option<BitMove> {
+        let san = san
+            .replace('0', "O")
+            .trim_end_matches(['+', '#', '!', '?'])
+            .to_string();
+        board.generate_moves().iter().cloned().find(|pleco_move| {
+            San::from_move(board, *pleco_move).trim_end_matches(['+', '#']) == san
+        })
+    }
+
+    pub fn castling(castle_type: CastleType, board_after: &Board) -> String {
+        let castling = match castle_type {
+            CastleType::KingSide => "O-O",
+            CastleType::QueenSide => "O-O-O",
+        };
+        format!("{}{}", castling, San::check_suffix(board_after))
+    }
+
+    fn check_suffix(board_after: &Board) -> &'static str {
+        if board_after.checkmate() {
+            "#"
+        } else if board_after.in_check() {
+            "+"
+        } else {
+            ""
+        }
+    }
+
+    fn file_char(file_index: u8) -> char {
+        (b'a' + file_index) as char
+    }
+
+    fn rank_char(rank_index: u8) -> char {
+        (b'1' + rank_index) as char
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(fen: &str) -> Board {
+        Board::from_fen(fen).unwrap()
+    }
+
+    #[test]
+    fn every_legal_move_round_trips_through_san() {
+        let positions = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+            "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2",
+        ];
+        for fen in positions {
+            let board = board(fen);
+            for pleco_move in board.generate_moves().iter() {
+                let san = San::from_move(&board, *pleco_move);
+                assert_eq!(
+                    San::to_move(&board, &san),
+                    Some(*pleco_move),
+                    "{} in {}",
+                    san,
+                    fen
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn disambiguates_by_file_then_rank_then_both() {
+        let knights = board("4k3/8/8/8/8/8/8/1N3N1K w - - 0 1");
+        assert_eq!(
+            San::from_uci_line(&knights, &[String::from("b1d2")]),
+            ["Nbd2"]
+        );
+        let rooks = board("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1");
+        assert_eq!(
+            San::from_uci_line(&rooks, &[String::from("a1a3")]),
+            ["R1a3"]
+        );
+        let queens = board("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1");
+        assert_eq!(
+            San::from_uci_line(&queens, &[String::from("a1b2")]),
+            ["Qa1b2"]
+        );
+    }
+
+    #[test]
+    fn writes_checks_mates_and_castling() {
+        let uci_line = |moves: &[&str]| -> Vec<String> {
+            moves.iter().map(|uci_move| uci_move.to_string()).collect()
+        };
+        assert_eq!(
+            San::from_uci_line(
+                &Board::start_pos(),
+                &uci_line(&["f2f3", "e7e5", "g2g4", "d8h4"])
+            ),
+            ["f3", "e5", "g4", "Qh4#"]
+        );
+        let castling = board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        assert_eq!(San::from_uci_line(&castling, &uci_line(&["e1g1"])), ["O-O"]);
+        let checking_castling = board("r3k3/8/8/8/8/8/8/3K4 b q - 0 1");
+        assert_eq!(
+            San::from_uci_line(&checking_castling, &uci_line(&["e8c8"])),
+            ["O-O-O+"]
+        );
+    }
+
+    #[test]
+    fn stops_a_uci_line_at_the_first_illegal_move() {
+        let line = ["e2e4", "e2e4", "e7e5"].map(String::from);
+        assert_eq!(
+            San::from_uci_line(&Board::start_pos(), &line),
+            ["e4", "e2e4"]
+        );
+    }
+
+    #[test]
+    fn reads_san_with_zeros_suffixes_and_annotations() {
+        let castling = board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let short = San::to_move(&castling, "0-0").unwrap();
+        assert!(short.is_king_castle());
+        let start = Board::start_pos();
+        assert_eq!(
+            San::to_move(&start, "Nf3!?").map(|pleco_move| pleco_move.stringify()),
+            Some(String::from("g1f3"))
+        );
+        assert_eq!(San::to_move(&start, "Nf4"), None);
+        assert_eq!(San::to_move(&start, "e5"), None);
+    }
+}
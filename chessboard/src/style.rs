@@ -0,0 +1,119 @@
+//! Change the appearance of a `ChessBoard`.
+//!
+//! The board draws itself with the `Appearance` its theme gives for its style. `iced::Theme`
+//! has a light and a dark appearance and picks the one matching the theme, and any other theme
+//! can implement `StyleSheet` to style the board its own way.
+
+use iced::{Color, Theme};
+
+/// The colors a `ChessBoard` is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Appearance {
+    pub background: Color,
+    pub light_squares: Color,
+    pub dark_squares: Color,
+    /// The square a dragged piece comes from.
+    pub drag_start: Color,
+    /// The square under a dragged piece.
+    pub drag_end: Color,
+    /// The rank and file of the square under a dragged piece.
+    pub drag_cross: Color,
+    pub coordinates: Color,
+    pub arrow: Color,
+    pub glyph: Color,
+}
+
+impl Appearance {
+    pub fn light() -> Self {
+        Self {
+            background: Color::from_rgb8(0x15, 0x88, 0xC4),
+            light_squares: Color::from_rgb8(0xFF, 0xDE, 0xAD),
+            dark_squares: Color::from_rgb8(0xCD, 0x85, 0x3F),
+            drag_start: Color::from_rgb8(0xDE, 0x18, 0x21),
+            drag_end: Color::from_rgb8(0x62, 0xC7, 0x39),
+            drag_cross: Color::from_rgb8(0x81, 0x44, 0xBD),
+            coordinates: Color::from_rgb8(0xFF, 0xFF, 0x00),
+            arrow: Color::from_rgba8(0x15, 0x65, 0xC0, 0.8),
+            glyph: Color::from_rgb8(0x2E, 0x7D, 0x32),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            background: Color::from_rgb8(0x26, 0x32, 0x38),
+            light_squares: Color::from_rgb8(0x90, 0xA4, 0xAE),
+            dark_squares: Color::from_rgb8(0x54, 0x6E, 0x7A),
+            drag_start: Color::from_rgb8(0xC6, 0x28, 0x28),
+            drag_end: Color::from_rgb8(0x43, 0xA0, 0x47),
+            drag_cross: Color::from_rgb8(0x6A, 0x1B, 0x9A),
+            coordinates: Color::from_rgb8(0xCF, 0xD8, 0xDC),
+            arrow: Color::from_rgba8(0xFF, 0xB3, 0x00, 0.8),
+            glyph: Color::from_rgb8(0x2E, 0x7D, 0x32),
+        }
+    }
+}
+
+impl Default for Appearance {
+    fn default() -> Self {
+        Appearance::light()
+    }
+}
+
+/// Gives the appearance of a `ChessBoard` for a style.
+pub trait StyleSheet {
+    type Style: Default;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance;
+}
+
+/// The style of a `ChessBoard` under `iced::Theme`.
+#[derive(Default)]
+pub enum BoardStyle {
+    /// The light or dark appearance, following the theme.
+    #[default]
+    Default,
+    Custom(Box<dyn StyleSheet<Style = Theme>>),
+}
+
+impl From<Appearance> for BoardStyle {
+    fn from(appearance: Appearance) -> Self {
+        BoardStyle::Custom(Box::new(appearance))
+    }
+}
+
+/// A fixed appearance, whatever the theme.
+impl StyleSheet for Appearance {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> Appearance {
+        *self
+    }
+}
+
+impl StyleSheet for Theme {
+    type Style = BoardStyle;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        match style {
+            BoardStyle::Default => {
+                if is_dark(self) {
+                    Appearance::dark()
+                } else {
+                    Appearance::light()
+                }
+            }
+            BoardStyle::Custom(custom) => custom.appearance(self),
+        }
+    }
+}
+
+fn is_dark(theme: &Theme) -> bool {
+    match theme {
+        Theme::Light => false,
+        Theme::Dark => true,
+        Theme::Custom(_) => {
+            let background = theme.palette().background;
+            background.r * 0.299 + background.g * 0.587 + background.b * 0.114 < 0.5
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+
+use crate::game_clock::TimeControl;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorArg {
+    White,
+    Black,
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "peer-chess",
+    version,
+    about = "Play chess against a peer or a UCI engine"
+)]
+pub struct Cli {
+    /// Start the game from this position
+    #[arg(long, conflicts_with = "pgn")]
+    pub fen: Option<String>,
+
+    /// Open the game stored in this PGN file
+    #[arg(long, value_name = "PATH")]
+    pub pgn: Option<PathBuf>,
+
+    /// The side you play, shown at the bottom of the board
+    #[arg(long, value_enum)]
+    pub color: Option<ColorArg>,
+
+    /// Play with a clock, as minutes+increment seconds, e.g. 5+3
+    #[arg(long, value_name = "MINUTES+SECONDS")]
+    pub time_control: Option<TimeControl>,
+
+    /// Play in the terminal instead of opening a window
+    #[arg(long)]
+    pub terminal: bool,
+
+    /// Read and save the settings in this file instead of the user configuration directory
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+}
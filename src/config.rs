@@ -0,0 +1,199 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// An RGB color, written as "#RRGGBB" in the configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RgbColor(pub u8, pub u8, pub u8);
+
+impl TryFrom<String> for RgbColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<RgbColor> for String {
+    fn from(color: RgbColor) -> Self {
+        color.to_string()
+    }
+}
+
+impl std::str::FromStr for RgbColor {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("{} is not a #RRGGBB color", value);
+        let hex = value.trim().strip_prefix('#').ok_or_else(invalid)?;
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16);
+        Ok(RgbColor(
+            channel(0).map_err(|_| invalid())?,
+            channel(2).map_err(|_| invalid())?,
+            channel(4).map_err(|_| invalid())?,
+        ))
+    }
+}
+
+impl fmt::Display for RgbColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.0, self.1, self.2)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    Light,
+    Dark,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 2] = [ThemeChoice::Light, ThemeChoice::Dark];
+}
+
+impl fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeChoice::Light => write!(f, "Light"),
+            ThemeChoice::Dark => write!(f, "Dark"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardColorKind {
+    Background,
+    LightSquares,
+    DarkSquares,
+    Coordinates,
+    Arrow,
+}
+
+impl BoardColorKind {
+    pub const ALL: [BoardColorKind; 5] = [
+        BoardColorKind::Background,
+        BoardColorKind::LightSquares,
+        BoardColorKind::DarkSquares,
+        BoardColorKind::Coordinates,
+        BoardColorKind::Arrow,
+    ];
+}
+
+impl fmt::Display for BoardColorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardColorKind::Background => write!(f, "Background"),
+            BoardColorKind::LightSquares => write!(f, "Light squares"),
+            BoardColorKind::DarkSquares => write!(f, "Dark squares"),
+            BoardColorKind::Coordinates => write!(f, "Coordinates"),
+            BoardColorKind::Arrow => write!(f, "Hint arrow"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoardColors {
+    pub background: RgbColor,
+    pub light_squares: RgbColor,
+    pub dark_squares: RgbColor,
+    pub coordinates: RgbColor,
+    pub arrow: RgbColor,
+}
+
+impl BoardColors {
+    pub fn get(&self, kind: BoardColorKind) -> RgbColor {
+        match kind {
+            BoardColorKind::Background => self.background,
+            BoardColorKind::LightSquares => self.light_squares,
+            BoardColorKind::DarkSquares => self.dark_squares,
+            BoardColorKind::Coordinates => self.coordinates,
+            BoardColorKind::Arrow => self.arrow,
+        }
+    }
+
+    pub fn set(&mut self, kind: BoardColorKind, color: RgbColor) {
+        match kind {
+            BoardColorKind::Background => self.background = color,
+            BoardColorKind::LightSquares => self.light_squares = color,
+            BoardColorKind::DarkSquares => self.dark_squares = color,
+            BoardColorKind::Coordinates => self.coordinates = color,
+            BoardColorKind::Arrow => self.arrow = color,
+        }
+    }
+}
+
+impl Default for BoardColors {
+    fn default() -> Self {
+        Self {
+            background: RgbColor(0x15, 0x88, 0xC4),
+            light_squares: RgbColor(0xFF, 0xDE, 0xAD),
+            dark_squares: RgbColor(0xCD, 0x85, 0x3F),
+            coordinates: RgbColor(0xFF, 0xFF, 0x00),
+            arrow: RgbColor(0x15, 0x65, 0xC0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub board_size: u16,
+    pub theme: ThemeChoice,
+    pub antialiasing: bool,
+    pub colors: BoardColors,
+    /// The Syzygy tables directory, empty when no tables are used.
+    pub tablebase_directory: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            board_size: 400,
+            theme: ThemeChoice::Light,
+            antialiasing: true,
+            colors: BoardColors::default(),
+            tablebase_directory: String::new(),
+        }
+    }
+}
+
+impl Config {
+    pub const BOARD_SIZES: [u16; 5] = [320, 400, 480, 560, 640];
+
+    pub fn path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "peer-chess").map(|dirs| dirs.config_dir().join(CONFIG_FILE_NAME))
+    }
+
+    /// Returns the default configuration when no configuration file was written yet.
+    pub fn load() -> io::Result<Self> {
+        match Config::path() {
+            Some(path) if path.exists() => Config::load_from(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = toml::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, content)
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use iced_chessboard::san::San;
+use pleco::Board;
+
+const ECO_DATASET: &str = include_str!("../resources/eco.tsv");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Opening {
+    pub eco: String,
+    pub name: String,
+    pub variation: Option<String>,
+}
+
+impl fmt::Display for Opening {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.variation {
+            Some(ref variation) => write!(f, "{} {}: {}", self.eco, self.name, variation),
+            None => write!(f, "{} {}", self.eco, self.name),
+        }
+    }
+}
+
+pub struct EcoTable {
+    openings: HashMap<String, Opening>,
+}
+
+impl EcoTable {
+    pub fn get() -> &'static EcoTable {
+        static TABLE: OnceLock<EcoTable> = OnceLock::new();
+        TABLE.get_or_init(EcoTable::parse)
+    }
+
+    pub fn classify(&self, positions: &[Board]) -> Option<&Opening> {
+        positions
+            .iter()
+            .rev()
+            .find_map(|position| self.lookup(position))
+    }
+
+    /// The opening named after exactly this position, if any.
+    pub fn lookup(&self, position: &Board) -> Option<&Opening> {
+        self.openings.get(&EcoTable::position_key(position))
+    }
+
+    fn parse() -> Self {
+        let mut openings = HashMap::new();
+        ECO_DATASET.lines().skip(1).for_each(|line| {
+            let mut fields = line.split('\t');
+            let (Some(eco), Some(name), Some(pgn)) = (fields.next(), fields.next(), fields.next())
+            else {
+                return;
+            };
+            if let Some(board) = EcoTable::play(pgn) {
+                openings
+                    .entry(EcoTable::position_key(&board))
+                    .or_insert_with(|| {
+                        let (name, variation) = match name.split_once(": ") {
+                            Some((name, variation)) => (name, Some(variation.to_string())),
+                            None => (name, None),
+                        };
+                        Opening {
+                            eco: eco.to_string(),
+                            name: name.to_string(),
+                            variation,
+                        }
+                    });
+            }
+        });
+        Self { openings }
+    }
+
+    fn play(pgn: &str) -> Option<Board> {
+        let mut board = Board::start_pos();
+        for san in pgn.split_whitespace().filter(|token| !token.ends_with('.')) {
+            board.apply_move(San::to_move(&board, san)?);
+        }
+        Some(board)
+    }
+
+    // Move counters and the en passant square are left out so that transpositions still match.
+    fn position_key(board: &Board) -> String {
+        board
+            .fen()
+            .split_whitespace()
+            .take(3)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(pgn: &str) -> Option<String> {
+        let mut positions = vec![Board::start_pos()];
+        for san in pgn.split_whitespace().filter(|token| !token.ends_with('.')) {
+            let mut board = positions.last().unwrap().clone();
+            board.apply_move(San::to_move(&board, san).unwrap());
+            positions.push(board);
+        }
+        EcoTable::get()
+            .classify(&positions)
+            .map(|opening| opening.to_string())
+    }
+
+    #[test]
+    fn every_dataset_line_is_playable() {
+        let unplayable: Vec<&str> = ECO_DATASET
+            .lines()
+            .skip(1)
+            .filter(|line| line.split('\t').nth(2).and_then(EcoTable::play).is_none())
+            .collect();
+        assert!(unplayable.is_empty(), "{:#?}", unplayable);
+    }
+
+    #[test]
+    fn every_eco_code_is_covered() {
+        let openings = &EcoTable::get().openings;
+        for volume in ['A', 'B', 'C', 'D', 'E'] {
+            for number in 0..100 {
+                let eco = format!("{}{:02}", volume, number);
+                assert!(
+                    openings.values().any(|opening| opening.eco == eco),
+                    "{} has no line",
+                    eco
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn classifies_known_lines() {
+        assert_eq!(
+            classify("1. e4 c5 2. Nf3 d6 3. d4 cxd4 4. Nxd4 Nf6 5. Nc3 a6 6. Be3").as_deref(),
+            Some("B90 Sicilian Defense: Najdorf Variation, English Attack")
+        );
+        assert_eq!(
+            classify("1. d4 d5 2. c4 e6 3. Nc3 Nf6 4. Bg5 Be7").as_deref(),
+            Some("D53 Queen's Gambit Declined: Modern Variation, Normal Line")
+        );
+        assert_eq!(
+            classify(
+                "1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 4. Ba4 Nf6 5. O-O Be7 6. Re1 b5 7. Bb3 O-O 8. c3 d5"
+            )
+            .as_deref(),
+            Some("C89 Ruy Lopez: Marshall Attack")
+        );
+    }
+
+    #[test]
+    fn keeps_the_last_named_position_once_out_of_book() {
+        assert_eq!(
+            classify("1. e4 e6 2. d4 d5 3. e5 c5 4. a3 a6 5. h3").as_deref(),
+            Some("C02 French Defense: Advance Variation")
+        );
+    }
+
+    #[test]
+    fn recognises_transpositions() {
+        assert_eq!(
+            classify("1. Nf3 Nf6 2. c4 e6 3. d4").as_deref(),
+            Some("E10 Indian Defense: Anti-Nimzo-Indian")
+        );
+    }
+
+    #[test]
+    fn unknown_first_moves_are_not_classified() {
+        assert_eq!(classify(""), None);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use pleco::Player;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeControl {
+    pub initial: Duration,
+    pub increment: Duration,
+}
+
+impl TimeControl {
+    pub const fn minutes(initial_minutes: u64, increment_seconds: u64) -> Self {
+        Self {
+            initial: Duration::from_secs(initial_minutes * 60),
+            increment: Duration::from_secs(increment_seconds),
+        }
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("{} is not a time control such as 5+3", value);
+        let (minutes, increment) = value.trim().split_once('+').ok_or_else(invalid)?;
+        let minutes: u64 = minutes.parse().map_err(|_| invalid())?;
+        let increment: u64 = increment.parse().map_err(|_| invalid())?;
+        if minutes == 0 {
+            return Err(invalid());
+        }
+        Ok(TimeControl::minutes(minutes, increment))
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}+{}",
+            self.initial.as_secs() / 60,
+            self.increment.as_secs()
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameClock {
+    time_control: TimeControl,
+    remaining: [Duration; 2],
+    turn: Player,
+    turn_started: Option<Instant>,
+    flagged: Option<Player>,
+}
+
+impl GameClock {
+    pub fn new(time_control: TimeControl, turn: Player) -> Self {
+        Self {
+            time_control,
+            remaining: [time_control.initial; 2],
+            turn,
+            turn_started: None,
+            flagged: None,
+        }
+    }
+
+    pub fn resume(
+        time_control: TimeControl,
+        white_remaining: Duration,
+        black_remaining: Duration,
+        turn: Player,
+    ) -> Self {
+        let mut clock = GameClock::new(time_control, turn);
+        clock.remaining = [white_remaining, black_remaining];
+        clock
+    }
+
+    pub fn time_control(&self) -> TimeControl {
+        self.time_control
+    }
+
+    pub fn start(&mut self) {
+        if self.flagged.is_none() && self.turn_started.is_none() {
+            self.turn_started = Some(Instant::now());
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.remaining[self.turn as usize] = self.remaining(self.turn);
+        self.turn_started = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.turn_started.is_some()
+    }
+
+    pub fn remaining(&self, player: Player) -> Duration {
+        let remaining = self.remaining[player as usize];
+        match self.turn_started {
+            Some(turn_started) if player == self.turn => {
+                remaining.saturating_sub(turn_started.elapsed())
+            }
+            _ => remaining,
+        }
+    }
+
+    pub fn switch_turn(&mut self) {
+        if self.flagged.is_some() {
+            return;
+        }
+        let running = self.is_running();
+        self.stop();
+        self.remaining[self.turn as usize] += self.time_control.increment;
+        self.turn = self.turn.other_player();
+        if running {
+            self.start();
+        }
+    }
+
+    pub fn tick(&mut self) -> Option<Player> {
+        if self.flagged.is_none() && self.is_running() && self.remaining(self.turn).is_zero() {
+            self.stop();
+            self.flagged = Some(self.turn);
+        }
+        self.flagged
+    }
+
+    pub fn flagged(&self) -> Option<Player> {
+        self.flagged
+    }
+
+    pub fn format(duration: Duration) -> String {
+        let tenths = duration.as_millis() / 100;
+        let seconds = tenths / 10;
+        if seconds < 10 {
+            format!("0:{:02}.{}", seconds, tenths % 10)
+        } else {
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use iced_chessboard::chess960::{Chess960, Chess960Castling};
+use iced_chessboard::position_setup::SetupError;
+use iced_chessboard::{GameStatus, MovableSides, MoveEvent, Moves};
+use pleco::{Board, Player};
+
+use crate::game_record::{GameRecord, RecordedMove};
+
+/// The current position and move history of a game, independent of how moves are entered.
+#[derive(Clone)]
+pub struct GameController {
+    board: Board,
+    chess960_castling: Option<Chess960Castling>,
+    record: GameRecord,
+    movable: MovableSides,
+}
+
+impl GameController {
+    pub fn new(board: Board, chess960_castling: Option<Chess960Castling>) -> Self {
+        Self {
+            record: GameRecord::starting_at(&board, chess960_castling.as_ref()),
+            board,
+            chess960_castling,
+            movable: MovableSides::Both,
+        }
+    }
+
+    pub fn from_fen(fen: &str) -> Result<Self, SetupError> {
+        let (board, chess960_castling) = Chess960::any_position_from_fen(fen)?;
+        Ok(GameController::new(board, chess960_castling))
+    }
+
+    /// Continues the game at the end of the main line of `record`.
+    pub fn from_record(record: GameRecord) -> Result<Self, String> {
+        let (board, chess960_castling) = record
+            .last_position()
+            .ok_or_else(|| String::from("The game could not be replayed"))?;
+        Ok(Self {
+            board,
+            chess960_castling,
+            record,
+            movable: MovableSides::Both,
+        })
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn chess960_castling(&self) -> Option<&Chess960Castling> {
+        self.chess960_castling.as_ref()
+    }
+
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    pub fn record_mut(&mut self) -> &mut GameRecord {
+        &mut self.record
+    }
+
+    pub fn turn(&self) -> Player {
+        self.board.turn()
+    }
+
+    pub fn fen(&self) -> String {
+        match self.chess960_castling {
+            Some(ref castling) => Chess960::fen(&self.board, castling),
+            None => self.board.fen(),
+        }
+    }
+
+    pub fn status(&self) -> GameStatus {
+        GameStatus::of(&self.board)
+    }
+
+    pub fn movable_sides(&self) -> MovableSides {
+        self.movable
+    }
+
+    pub fn set_movable_sides(&mut self, movable: MovableSides) {
+        self.movable = movable;
+    }
+
+    /// Whether the side to move may be moved by the local player.
+    pub fn can_move(&self) -> bool {
+        self.can_move_side(self.board.turn())
+    }
+
+    pub fn can_move_side(&self, player: Player) -> bool {
+        self.movable.allows(player) && self.status() == GameStatus::Ongoing
+    }
+
+    /// Plays a move written in SAN or UCI notation.
+    pub fn play_notation(&mut self, notation: &str) -> Option<MoveEvent> {
+        if !self.can_move() {
+            return None;
+        }
+        let castling = self.chess960_castling.as_ref();
+        let (new_board, new_castling) = Moves::play_san_move(&self.board, castling, notation)
+            .or_else(|| Moves::play_uci_move(&self.board, castling, notation))?;
+        self.apply_position(new_board, new_castling)
+    }
+
+    /// Plays a move in UCI notation, ignoring which sides the local player may move, as for
+    /// moves chosen by an engine or a peer.
+    pub fn play_uci_move(&mut self, uci_move: &str) -> Option<MoveEvent> {
+        if self.status() != GameStatus::Ongoing {
+            return None;
+        }
+        let (new_board, new_castling) =
+            Moves::play_uci_move(&self.board, self.chess960_castling.as_ref(), uci_move)?;
+        self.apply_position(new_board, new_castling)
+    }
+
+    /// Moves on to a position reached from the current one by a single legal move, leaving the
+    /// game untouched for any other position.
+    fn apply_position(
+        &mut self,
+        new_board: Board,
+        new_castling: Option<Chess960Castling>,
+    ) -> Option<MoveEvent> {
+        let event = MoveEvent::between(
+            &self.board,
+            self.chess960_castling.as_ref(),
+            &new_board,
+            new_castling.as_ref(),
+        )?;
+        self.board = new_board;
+        self.chess960_castling = new_castling;
+        self.record
+            .push(RecordedMove::new(event.san.clone(), event.uci.clone()));
+        Some(event)
+    }
+
+    /// Moves the live position to the end of the main line, after the record was edited.
+    pub fn sync_to_mainline(&mut self) {
+        if let Some((board, chess960_castling)) = self.record.last_position() {
+            self.board = board;
+            self.chess960_castling = chess960_castling;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_record::STANDARD_START_FEN;
+
+    #[test]
+    fn records_every_applied_move() {
+        let mut game = GameController::from_fen(STANDARD_START_FEN).unwrap();
+        let event = game.play_notation("e4").unwrap();
+        assert_eq!(event.uci, "e2e4");
+        game.play_uci_move("e7e5").unwrap();
+        assert_eq!(game.record().moves().len(), 2);
+        assert_eq!(
+            game.fen(),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+        );
+    }
+
+    #[test]
+    fn refuses_positions_not_reached_by_a_legal_move() {
+        let mut game = GameController::from_fen(STANDARD_START_FEN).unwrap();
+        let (two_moves_later, _) = Chess960::any_position_from_fen(
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2",
+        )
+        .unwrap();
+        assert!(game.apply_position(two_moves_later, None).is_none());
+        assert!(game.play_notation("e5").is_none());
+        assert_eq!(game.fen(), STANDARD_START_FEN);
+        assert!(game.record().moves().is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredGame {
+    pub opponent: String,
+    pub date: String,
+    pub result: String,
+    pub time_control: Option<String>,
+    pub opening: Option<String>,
+    pub pgn: String,
+}
+
+/// Finished games, stored one JSON object per line so that adding a game only appends to the file.
+pub struct GameDatabase {
+    path: PathBuf,
+    games: Vec<StoredGame>,
+}
+
+impl GameDatabase {
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+        let games = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("game {} is corrupted: {}", index + 1, err),
+                    )
+                })
+            })
+            .collect::<io::Result<Vec<StoredGame>>>()?;
+        Ok(Self { path, games })
+    }
+
+    pub fn games(&self) -> &[StoredGame] {
+        &self.games
+    }
+
+    pub fn add(&mut self, game: StoredGame) -> io::Result<()> {
+        let line = serde_json::to_string(&game)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        self.games.push(game);
+        Ok(())
+    }
+
+    /// Returns the indices of the matching games, most recent first. Text filters match
+    /// case-insensitively anywhere in the field, and empty filters match every game.
+    pub fn search(&self, opponent: &str, result: Option<&str>, opening: &str) -> Vec<usize> {
+        let contains = |field: &str, filter: &str| {
+            filter.is_empty() || field.to_lowercase().contains(&filter.trim().to_lowercase())
+        };
+        (0..self.games.len())
+            .rev()
+            .filter(|index| {
+                let game = &self.games[*index];
+                contains(&game.opponent, opponent)
+                    && result.is_none_or(|result| game.result == result)
+                    && contains(game.opening.as_deref().unwrap_or_default(), opening)
+            })
+            .collect()
+    }
+}
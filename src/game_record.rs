@@ -0,0 +1,971 @@
+use iced_chessboard::chess960::{Chess960, Chess960Castling};
+use iced_chessboard::position_setup::PositionSetup;
+use iced_chessboard::Moves;
+use pleco::{Board, Player, SQ};
+
+use crate::eco::{EcoTable, Opening};
+use crate::pgn::{Pgn, PgnToken};
+
+pub const STANDARD_START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+const PGN_LINE_WIDTH: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Standard,
+    Chess960,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedMove {
+    pub san: String,
+    pub uci: String,
+    pub nags: Vec<u8>,
+    pub comment: Option<String>,
+    pub variations: Vec<Vec<RecordedMove>>,
+}
+
+impl RecordedMove {
+    pub fn new(san: String, uci: String) -> Self {
+        Self {
+            san,
+            uci,
+            nags: vec![],
+            comment: None,
+            variations: vec![],
+        }
+    }
+
+    pub fn glyphs(&self) -> String {
+        self.nags
+            .iter()
+            .filter_map(|nag| Pgn::nag_glyph(*nag))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn destination(&self) -> Option<SQ> {
+        let src = Moves::parse_square(self.uci.get(0..2)?)?;
+        // Chess960 castling is stored as the king taking its own rook.
+        let castling_file = if self.san.starts_with("O-O-O") {
+            Some(2)
+        } else if self.san.starts_with("O-O") {
+            Some(6)
+        } else {
+            None
+        };
+        match castling_file {
+            Some(file) => Some(SQ(src.rank_idx_of_sq() * 8 + file)),
+            None => Moves::parse_square(self.uci.get(2..4)?),
+        }
+    }
+
+    pub fn toggle_nag(&mut self, nag: u8) {
+        if self.nags.contains(&nag) {
+            self.nags.retain(|other| *other != nag);
+        } else {
+            self.nags.retain(|other| !Pgn::same_nag_group(*other, nag));
+            self.nags.push(nag);
+            self.nags.sort_unstable();
+        }
+    }
+}
+
+/// Locates a move in the variation tree: the index in the main line, then for every nested
+/// variation the variation number at the branching move followed by the index inside it.
+/// The empty path is the starting position.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MovePath(Vec<usize>);
+
+impl MovePath {
+    pub fn start() -> Self {
+        Self(vec![])
+    }
+
+    pub fn is_start(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn is_in_variation(&self) -> bool {
+        self.0.len() > 1
+    }
+
+    pub fn ply(&self) -> usize {
+        self.0.iter().step_by(2).sum()
+    }
+
+    pub fn child(&self, index: usize) -> Self {
+        let mut steps = self.0.clone();
+        steps.push(index);
+        Self(steps)
+    }
+
+    pub fn variation(&self, variation: usize, index: usize) -> Self {
+        let mut steps = self.0.clone();
+        steps.extend([variation, index]);
+        Self(steps)
+    }
+
+    fn line_prefix(&self) -> &[usize] {
+        &self.0[..self.0.len().saturating_sub(1)]
+    }
+
+    fn with_index(&self, index: usize) -> Self {
+        Self(self.line_prefix().to_vec()).child(index)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    variant: Variant,
+    start_fen: String,
+    date: String,
+    white: Option<String>,
+    black: Option<String>,
+    moves: Vec<RecordedMove>,
+}
+
+impl GameRecord {
+    pub fn new(variant: Variant, start_fen: String) -> Self {
+        Self {
+            variant,
+            start_fen,
+            date: chrono::Local::now().format("%Y.%m.%d").to_string(),
+            white: None,
+            black: None,
+            moves: vec![],
+        }
+    }
+
+    pub fn starting_at(board: &Board, chess960_castling: Option<&Chess960Castling>) -> Self {
+        match chess960_castling {
+            Some(castling) => GameRecord::new(Variant::Chess960, Chess960::fen(board, castling)),
+            None => GameRecord::new(Variant::Standard, board.fen()),
+        }
+    }
+
+    pub fn start_fen(&self) -> &str {
+        &self.start_fen
+    }
+
+    pub fn date(&self) -> &str {
+        &self.date
+    }
+
+    pub fn player_name(&self, player: Player) -> Option<&str> {
+        match player {
+            Player::White => self.white.as_deref(),
+            Player::Black => self.black.as_deref(),
+        }
+    }
+
+    pub fn set_player_name(&mut self, player: Player, name: &str) {
+        let name = Some(name.trim().to_string()).filter(|name| !name.is_empty() && name != "?");
+        match player {
+            Player::White => self.white = name,
+            Player::Black => self.black = name,
+        }
+    }
+
+    pub fn moves(&self) -> &[RecordedMove] {
+        &self.moves
+    }
+
+    pub fn push(&mut self, recorded_move: RecordedMove) {
+        self.moves.push(recorded_move);
+    }
+
+    /// Adds a review verdict to a main-line move. A move assessment the move already carries
+    /// is kept, and the verdict is appended to any existing comment.
+    pub fn annotate(&mut self, index: usize, nag: u8, comment: &str) {
+        if let Some(recorded_move) = self.moves.get_mut(index) {
+            if !recorded_move
+                .nags
+                .iter()
+                .any(|other| Pgn::same_nag_group(*other, nag))
+            {
+                recorded_move.nags.push(nag);
+                recorded_move.nags.sort_unstable();
+            }
+            match recorded_move.comment {
+                Some(ref existing) if existing.contains(comment) => {}
+                Some(ref mut existing) => {
+                    existing.push(' ');
+                    existing.push_str(comment);
+                }
+                None => recorded_move.comment = Some(comment.to_string()),
+            }
+        }
+    }
+
+    pub fn move_number_prefix(&self, index: usize) -> String {
+        let start_fields: Vec<&str> = self.start_fen.split_whitespace().collect();
+        let start_fullmove_number: usize = start_fields
+            .get(5)
+            .and_then(|number| number.parse().ok())
+            .unwrap_or(1);
+        let ply = index + usize::from(start_fields.get(1) == Some(&"b"));
+        let fullmove_number = start_fullmove_number + ply / 2;
+        if ply % 2 == 0 {
+            format!("{}.", fullmove_number)
+        } else {
+            format!("{}...", fullmove_number)
+        }
+    }
+
+    pub fn is_chess960(&self) -> bool {
+        self.variant == Variant::Chess960
+    }
+
+    pub fn node(&self, path: &MovePath) -> Option<&RecordedMove> {
+        let index = *path.0.last()?;
+        GameRecord::line(&self.moves, path.line_prefix())?.get(index)
+    }
+
+    pub fn node_mut(&mut self, path: &MovePath) -> Option<&mut RecordedMove> {
+        let index = *path.0.last()?;
+        GameRecord::line_mut(&mut self.moves, path.line_prefix())?.get_mut(index)
+    }
+
+    pub fn mainline_end(&self) -> MovePath {
+        match self.moves.len() {
+            0 => MovePath::start(),
+            moves_count => MovePath::start().child(moves_count - 1),
+        }
+    }
+
+    pub fn same_mainline(&self, other: &GameRecord) -> bool {
+        self.moves.len() == other.moves.len()
+            && self
+                .moves
+                .iter()
+                .zip(&other.moves)
+                .all(|(recorded_move, other_move)| recorded_move.uci == other_move.uci)
+    }
+
+    pub fn previous_path(&self, path: &MovePath) -> MovePath {
+        let mut steps = path.0.clone();
+        // Stepping back from the first move of a variation leads to the position before the
+        // move it replaces, so the loop climbs up the tree until an index can be decreased.
+        while let Some(index) = steps.last_mut() {
+            if *index > 0 {
+                *index -= 1;
+                return MovePath(steps);
+            }
+            if steps.len() == 1 {
+                break;
+            }
+            steps.truncate(steps.len() - 2);
+        }
+        MovePath::start()
+    }
+
+    pub fn next_path(&self, path: &MovePath) -> Option<MovePath> {
+        let next = match path.0.last() {
+            Some(index) => path.with_index(index + 1),
+            None => MovePath::start().child(0),
+        };
+        self.node(&next).map(|_| next)
+    }
+
+    pub fn position_at(&self, path: &MovePath) -> Option<(Board, Option<Chess960Castling>)> {
+        let mut moves = vec![];
+        let mut line = &self.moves;
+        for step in path.0.chunks(2) {
+            match *step {
+                [index, variation] => {
+                    moves.extend(line.get(..index)?);
+                    line = line.get(index)?.variations.get(variation)?;
+                }
+                [index] => moves.extend(line.get(..=index)?),
+                _ => unreachable!(),
+            }
+        }
+        let expected_count = moves.len() + 1;
+        let mut positions = self.play_line(moves.into_iter());
+        if positions.len() == expected_count {
+            positions.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Plays the move after the given path, following an existing line when it already holds
+    /// that move, and returns the path of the played move.
+    pub fn add_move(&mut self, path: &MovePath, recorded_move: RecordedMove) -> Option<MovePath> {
+        let next_index = path.0.last().map(|index| index + 1).unwrap_or(0);
+        let next_path = path.with_index(next_index);
+        let line = GameRecord::line_mut(&mut self.moves, path.line_prefix())?;
+        let next_move = match line.get_mut(next_index) {
+            Some(next_move) => next_move,
+            None => {
+                line.push(recorded_move);
+                return Some(next_path);
+            }
+        };
+        if next_move.uci == recorded_move.uci {
+            return Some(next_path);
+        }
+        let variation = next_move
+            .variations
+            .iter()
+            .position(|variation| variation[0].uci == recorded_move.uci)
+            .unwrap_or_else(|| {
+                next_move.variations.push(vec![recorded_move]);
+                next_move.variations.len() - 1
+            });
+        Some(next_path.variation(variation, 0))
+    }
+
+    /// Moves the variation holding the path one rank up, making the first variation the
+    /// continuation of its parent line. Returns the new path of the same move.
+    pub fn promote_variation(&mut self, path: &MovePath) -> MovePath {
+        if !path.is_in_variation() {
+            return path.clone();
+        }
+        let steps = &path.0;
+        let (parent, branch) = steps.split_at(steps.len() - 3);
+        let (branch_index, variation, index) = (branch[0], branch[1], branch[2]);
+        let line = match GameRecord::line_mut(&mut self.moves, parent) {
+            Some(line) => line,
+            None => return path.clone(),
+        };
+        let parent_path = MovePath(parent.to_vec());
+        if variation > 0 {
+            line[branch_index].variations.swap(variation, variation - 1);
+            return parent_path
+                .child(branch_index)
+                .variation(variation - 1, index);
+        }
+        GameRecord::swap_with_variation(line, branch_index, 0);
+        parent_path.child(branch_index + index)
+    }
+
+    /// Moves the line holding the path one rank down: a variation swaps with the next one,
+    /// and a continuation gives way to the first alternative at its nearest branching move.
+    /// Demoting the last variation leaves the tree unchanged.
+    pub fn demote_variation(&mut self, path: &MovePath) -> MovePath {
+        let steps = &path.0;
+        if let Some(index) = steps.last().copied() {
+            if path.is_in_variation() {
+                let (parent, branch) = steps.split_at(steps.len() - 3);
+                let (branch_index, variation) = (branch[0], branch[1]);
+                if let Some(line) = GameRecord::line_mut(&mut self.moves, parent) {
+                    let variations = &mut line[branch_index].variations;
+                    if variation + 1 < variations.len() {
+                        variations.swap(variation, variation + 1);
+                        return MovePath(parent.to_vec())
+                            .child(branch_index)
+                            .variation(variation + 1, index);
+                    }
+                }
+                // The last variation is already ranked lowest.
+                return path.clone();
+            }
+            let prefix = path.line_prefix();
+            if let Some(line) = GameRecord::line_mut(&mut self.moves, prefix) {
+                let branch_index = (0..=index).rev().find(|branch_index| {
+                    line.get(*branch_index)
+                        .map(|recorded_move| !recorded_move.variations.is_empty())
+                        .unwrap_or(false)
+                });
+                if let Some(branch_index) = branch_index {
+                    GameRecord::swap_with_variation(line, branch_index, 0);
+                    return MovePath(prefix.to_vec())
+                        .child(branch_index)
+                        .variation(0, index - branch_index);
+                }
+            }
+        }
+        path.clone()
+    }
+
+    /// Removes the variation holding the path and returns the position it branched from.
+    pub fn delete_variation(&mut self, path: &MovePath) -> MovePath {
+        if !path.is_in_variation() {
+            return path.clone();
+        }
+        let steps = &path.0;
+        let (parent, branch) = steps.split_at(steps.len() - 3);
+        if let Some(line) = GameRecord::line_mut(&mut self.moves, parent) {
+            line[branch[0]].variations.remove(branch[1]);
+        }
+        self.previous_path(&MovePath(parent.to_vec()).child(branch[0]))
+    }
+
+    fn swap_with_variation(line: &mut Vec<RecordedMove>, branch_index: usize, variation: usize) {
+        let mut continuation = line.split_off(branch_index);
+        let mut promoted = continuation[0].variations.remove(variation);
+        let mut alternatives = std::mem::take(&mut continuation[0].variations);
+        alternatives.insert(variation, continuation);
+        promoted[0].variations = alternatives;
+        line.extend(promoted);
+    }
+
+    fn line<'a>(moves: &'a Vec<RecordedMove>, prefix: &[usize]) -> Option<&'a Vec<RecordedMove>> {
+        match *prefix {
+            [] => Some(moves),
+            [index, variation, ref rest @ ..] => {
+                GameRecord::line(moves.get(index)?.variations.get(variation)?, rest)
+            }
+            _ => None,
+        }
+    }
+
+    fn line_mut<'a>(
+        moves: &'a mut Vec<RecordedMove>,
+        prefix: &[usize],
+    ) -> Option<&'a mut Vec<RecordedMove>> {
+        match *prefix {
+            [] => Some(moves),
+            [index, variation, ref rest @ ..] => {
+                GameRecord::line_mut(moves.get_mut(index)?.variations.get_mut(variation)?, rest)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn from_pgn(pgn: &str) -> Result<Self, String> {
+        let tags = Pgn::tags(pgn);
+        let tag = |name: &str| {
+            tags.iter()
+                .find(|(tag_name, _)| tag_name == name)
+                .map(|(_, value)| value.as_str())
+        };
+        let variant = match tag("Variant") {
+            Some(variant) if variant.contains("960") => Variant::Chess960,
+            _ => Variant::Standard,
+        };
+        let start_fen = tag("FEN").unwrap_or(STANDARD_START_FEN).to_string();
+        let mut record = GameRecord::new(variant, start_fen);
+        if let Some(date) = tag("Date") {
+            record.date = date.to_string();
+        }
+        record.set_player_name(Player::White, tag("White").unwrap_or_default());
+        record.set_player_name(Player::Black, tag("Black").unwrap_or_default());
+        record
+            .start_position()
+            .ok_or_else(|| String::from("The FEN tag is not a valid position"))?;
+
+        let mut current = MovePath::start();
+        let mut variation_stack = vec![];
+        for token in Pgn::tokens(&Pgn::movetext(pgn)) {
+            match token {
+                PgnToken::Move(san) => {
+                    let next_ply = if current.is_start() {
+                        0
+                    } else {
+                        current.ply() + 1
+                    };
+                    let move_number = record.move_number_prefix(next_ply);
+                    let illegal_move = || format!("Illegal move {} {}", move_number, san);
+                    let (board, castling) =
+                        record.position_at(&current).ok_or_else(illegal_move)?;
+                    let (new_board, _) = Moves::play_san_move(&board, castling.as_ref(), &san)
+                        .ok_or_else(illegal_move)?;
+                    let recorded_move =
+                        GameRecord::identify_move(&board, castling.as_ref(), &new_board)
+                            .ok_or_else(illegal_move)?;
+                    current = record
+                        .add_move(&current, recorded_move)
+                        .ok_or_else(illegal_move)?;
+                }
+                PgnToken::Nag(nag) => {
+                    if let Some(recorded_move) = record.node_mut(&current) {
+                        if !recorded_move.nags.contains(&nag) {
+                            recorded_move.nags.push(nag);
+                        }
+                    }
+                }
+                PgnToken::Comment(comment) => {
+                    if let Some(recorded_move) = record.node_mut(&current) {
+                        recorded_move.comment = Some(comment);
+                    }
+                }
+                PgnToken::VariationStart => {
+                    let branch_position = record.previous_path(&current);
+                    variation_stack.push(std::mem::replace(&mut current, branch_position));
+                }
+                PgnToken::VariationEnd => {
+                    current = variation_stack
+                        .pop()
+                        .ok_or_else(|| String::from("Unbalanced parenthesis in the movetext"))?;
+                }
+                PgnToken::Result(_) => break,
+            }
+        }
+        Ok(record)
+    }
+
+    pub fn last_position(&self) -> Option<(Board, Option<Chess960Castling>)> {
+        self.replay().pop()
+    }
+
+    pub fn positions(&self) -> Vec<Board> {
+        self.replay().into_iter().map(|(board, _)| board).collect()
+    }
+
+    fn start_position(&self) -> Option<(Board, Option<Chess960Castling>)> {
+        match self.variant {
+            Variant::Chess960 => Chess960::position_from_fen(&self.start_fen)
+                .map(|(board, castling)| (board, Some(castling)))
+                .ok(),
+            Variant::Standard => PositionSetup::board_from_fen(&self.start_fen)
+                .map(|board| (board, None))
+                .ok(),
+        }
+    }
+
+    fn replay(&self) -> Vec<(Board, Option<Chess960Castling>)> {
+        self.play_line(self.moves.iter())
+    }
+
+    fn play_line<'a>(
+        &self,
+        moves: impl Iterator<Item = &'a RecordedMove>,
+    ) -> Vec<(Board, Option<Chess960Castling>)> {
+        let (mut board, mut castling) = match self.start_position() {
+            Some(start) => start,
+            None => return vec![],
+        };
+
+        let mut positions = vec![(board.clone(), castling)];
+        for recorded_move in moves {
+            match Moves::play_uci_move(&board, castling.as_ref(), &recorded_move.uci) {
+                Some((new_board, new_castling)) => {
+                    board = new_board;
+                    castling = new_castling;
+                    positions.push((board.clone(), castling));
+                }
+                None => break,
+            }
+        }
+        positions
+    }
+
+    pub fn opening(&self) -> Option<&'static Opening> {
+        if self.is_chess960() {
+            return None;
+        }
+        EcoTable::get().classify(&self.positions())
+    }
+
+    pub fn identify_move(
+        previous: &Board,
+        chess960_castling: Option<&Chess960Castling>,
+        new_board: &Board,
+    ) -> Option<RecordedMove> {
+        Moves::identify_move(previous, chess960_castling, new_board)
+            .map(|(san, uci)| RecordedMove::new(san, uci))
+    }
+
+    pub fn to_pgn(&self, result: &str) -> String {
+        let mut tags = vec![
+            ("Event", String::from("Peer chess game")),
+            ("Site", String::from("?")),
+            ("Date", self.date.clone()),
+            ("Round", String::from("-")),
+            ("White", String::from(self.white.as_deref().unwrap_or("?"))),
+            ("Black", String::from(self.black.as_deref().unwrap_or("?"))),
+            ("Result", String::from(result)),
+        ];
+        if self.variant == Variant::Chess960 {
+            tags.push(("Variant", String::from("Chess960")));
+        }
+        if let Some(opening) = self.opening() {
+            tags.push(("ECO", opening.eco.clone()));
+            tags.push(("Opening", opening.name.clone()));
+            if let Some(ref variation) = opening.variation {
+                tags.push(("Variation", variation.clone()));
+            }
+        }
+        if self.variant == Variant::Chess960 || self.start_fen != STANDARD_START_FEN {
+            tags.push(("SetUp", String::from("1")));
+            tags.push(("FEN", self.start_fen.clone()));
+        }
+
+        let mut pgn: String = tags
+            .into_iter()
+            .map(|(name, value)| format!("[{} \"{}\"]\n", name, value.replace('"', "\\\"")))
+            .collect();
+        pgn.push('\n');
+        pgn.push_str(&self.movetext(result));
+        pgn.push('\n');
+        pgn
+    }
+
+    fn movetext(&self, result: &str) -> String {
+        let mut tokens: Vec<String> = vec![];
+        self.line_tokens(&self.moves, 0, &mut tokens);
+        tokens.push(String::from(result));
+
+        let mut lines: Vec<String> = vec![];
+        let mut current_line = String::new();
+        tokens.into_iter().for_each(|token| {
+            if !current_line.is_empty() && current_line.len() + token.len() + 1 > PGN_LINE_WIDTH {
+                lines.push(current_line.clone());
+                current_line.clear();
+            }
+            if !current_line.is_empty() {
+                current_line.push(' ');
+            }
+            current_line.push_str(&token);
+        });
+        lines.push(current_line);
+        lines.join("\n")
+    }
+
+    fn line_tokens(&self, line: &[RecordedMove], first_ply: usize, tokens: &mut Vec<String>) {
+        line.iter().enumerate().for_each(|(index, recorded_move)| {
+            let move_number = self.move_number_prefix(first_ply + index);
+            let interrupted = index == 0
+                || line[index - 1].comment.is_some()
+                || !line[index - 1].variations.is_empty();
+            if !move_number.ends_with("...") || interrupted {
+                tokens.push(move_number);
+            }
+            tokens.push(recorded_move.san.clone());
+            tokens.extend(recorded_move.nags.iter().map(|nag| format!("${}", nag)));
+            if let Some(ref comment) = recorded_move.comment {
+                tokens.extend(
+                    format!("{{{}}}", comment)
+                        .split_whitespace()
+                        .map(String::from),
+                );
+            }
+            recorded_move.variations.iter().for_each(|variation| {
+                let mut variation_tokens = vec![];
+                self.line_tokens(variation, first_ply + index, &mut variation_tokens);
+                if let Some(first) = variation_tokens.first_mut() {
+                    first.insert(0, '(');
+                }
+                if let Some(last) = variation_tokens.last_mut() {
+                    last.push(')');
+                }
+                tokens.extend(variation_tokens);
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sans(line: &[RecordedMove]) -> Vec<&str> {
+        line.iter()
+            .map(|recorded_move| recorded_move.san.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn reads_pgn_with_glued_numbers_line_comments_and_glyphs() {
+        let record = GameRecord::from_pgn(
+            "[Date \"2023.04.01\"]\n\n1.e4 e5 2.Nf3!? ; develops\n2...Nc6 3.Bb5 a6?! 1-0 4. Ba4",
+        )
+        .unwrap();
+        assert_eq!(record.date(), "2023.04.01");
+        assert_eq!(
+            sans(record.moves()),
+            ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"]
+        );
+        assert_eq!(record.moves()[2].nags, [5]);
+        assert_eq!(record.moves()[2].comment.as_deref(), Some("develops"));
+        assert_eq!(record.moves()[5].nags, [6]);
+        assert_eq!(record.moves()[2].uci, "g1f3");
+    }
+
+    #[test]
+    fn reads_and_writes_player_names() {
+        let mut record =
+            GameRecord::from_pgn("[White \"Anna\"]\n[Black \"?\"]\n\n1. e4 *").unwrap();
+        assert_eq!(record.player_name(Player::White), Some("Anna"));
+        assert_eq!(record.player_name(Player::Black), None);
+        record.set_player_name(Player::Black, " Ben ");
+        let pgn = record.to_pgn("*");
+        assert!(pgn.contains("[White \"Anna\"]\n[Black \"Ben\"]"));
+    }
+
+    #[test]
+    fn reads_variations_as_alternatives_to_the_move_they_follow() {
+        let record = GameRecord::from_pgn("1. e4 e5 (1... c5 2. Nf3) 2. Nf3 *").unwrap();
+        assert_eq!(sans(record.moves()), ["e4", "e5", "Nf3"]);
+        assert_eq!(sans(&record.moves()[1].variations[0]), ["c5", "Nf3"]);
+    }
+
+    #[test]
+    fn starts_from_the_fen_tag() {
+        let record = GameRecord::from_pgn(
+            "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 40\"]\n\n40... Kd7 41. e4 *",
+        )
+        .unwrap();
+        assert_eq!(sans(record.moves()), ["Kd7", "e4"]);
+        assert_eq!(record.move_number_prefix(0), "40...");
+    }
+
+    #[test]
+    fn rejects_broken_movetext() {
+        assert_eq!(
+            GameRecord::from_pgn("1. e4 e5 2. Ke3").unwrap_err(),
+            "Illegal move 2. Ke3"
+        );
+        assert_eq!(
+            GameRecord::from_pgn("1. e4 e5) 2. Nf3").unwrap_err(),
+            "Unbalanced parenthesis in the movetext"
+        );
+        assert_eq!(
+            GameRecord::from_pgn("[FEN \"not a position\"]\n\n1. e4").unwrap_err(),
+            "The FEN tag is not a valid position"
+        );
+    }
+
+    const TREE: &str =
+        "1. e4 $1 {best by test} e5 (1... c5 $5 2. Nf3 (2. Nc3 {closed} Nc6) (2. c3) \
+                        d6) (1... e6 2. d4 (2. Nf3) d5) 2. Nf3 Nc6 $10 *";
+
+    fn tree() -> GameRecord {
+        GameRecord::from_pgn(TREE).unwrap()
+    }
+
+    fn path(steps: &[usize]) -> MovePath {
+        MovePath(steps.to_vec())
+    }
+
+    fn san_at(record: &GameRecord, steps: &[usize]) -> Option<String> {
+        record
+            .node(&path(steps))
+            .map(|recorded_move| recorded_move.san.clone())
+    }
+
+    #[test]
+    fn nested_variations_round_trip_through_pgn() {
+        let record = tree();
+        let pgn = record.to_pgn("*");
+        let movetext = pgn.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert!(movetext.contains(
+            "1. e4 $1 {best by test} 1... e5 (1... c5 $5 2. Nf3 (2. Nc3 {closed} 2... Nc6) (2. \
+             c3) 2... d6) (1... e6 2. d4 (2. Nf3) 2... d5) 2. Nf3 Nc6 $10 *"
+        ));
+        let reread = GameRecord::from_pgn(&pgn).unwrap();
+        assert_eq!(reread.to_pgn("*"), pgn);
+        assert_eq!(san_at(&reread, &[1, 0, 1, 0, 1]).as_deref(), Some("Nc6"));
+        assert_eq!(
+            reread
+                .node(&path(&[1, 0, 1, 0, 0]))
+                .unwrap()
+                .comment
+                .as_deref(),
+            Some("closed")
+        );
+        assert_eq!(reread.node(&path(&[1, 0, 0])).unwrap().nags, [5]);
+    }
+
+    #[test]
+    fn paths_count_plies_and_step_through_variations() {
+        let record = tree();
+        assert_eq!(path(&[1, 0, 1, 0, 1]).ply(), 3);
+        assert_eq!(record.previous_path(&path(&[1, 0, 0])), path(&[0]));
+        assert_eq!(
+            record.previous_path(&path(&[1, 0, 1, 0, 0])),
+            path(&[1, 0, 0])
+        );
+        assert_eq!(record.previous_path(&path(&[0])), MovePath::start());
+        assert_eq!(record.next_path(&MovePath::start()), Some(path(&[0])));
+        assert_eq!(record.next_path(&path(&[1, 0, 1])), Some(path(&[1, 0, 2])));
+        assert_eq!(record.next_path(&path(&[1, 0, 2])), None);
+    }
+
+    #[test]
+    fn position_at_follows_nested_variations() {
+        let record = tree();
+        let expected = GameRecord::from_pgn("1. e4 c5 2. Nc3 Nc6 *")
+            .unwrap()
+            .last_position()
+            .unwrap()
+            .0;
+        let (position, _) = record.position_at(&path(&[1, 0, 1, 0, 1])).unwrap();
+        assert_eq!(position.fen(), expected.fen());
+        assert!(record.position_at(&path(&[1, 0, 1, 0, 2])).is_none());
+        assert!(record.position_at(&path(&[1, 2, 0])).is_none());
+    }
+
+    #[test]
+    fn add_move_follows_existing_lines_and_branches_new_ones() {
+        let mut record = tree();
+        let e5 = RecordedMove::new(String::from("e5"), String::from("e7e5"));
+        let c5 = RecordedMove::new(String::from("c5"), String::from("c7c5"));
+        let c6 = RecordedMove::new(String::from("c6"), String::from("c7c6"));
+        let nc3 = RecordedMove::new(String::from("Nc3"), String::from("b1c3"));
+        assert_eq!(record.add_move(&path(&[0]), e5), Some(path(&[1])));
+        assert_eq!(record.add_move(&path(&[0]), c5), Some(path(&[1, 0, 0])));
+        assert_eq!(record.add_move(&path(&[0]), c6), Some(path(&[1, 2, 0])));
+        assert_eq!(
+            record.add_move(&path(&[1, 0, 2]), nc3),
+            Some(path(&[1, 0, 3]))
+        );
+        assert_eq!(
+            sans(&record.moves()[1].variations[0]),
+            ["c5", "Nf3", "d6", "Nc3"]
+        );
+        assert_eq!(sans(record.moves()), ["e4", "e5", "Nf3", "Nc6"]);
+    }
+
+    #[test]
+    fn promotes_variations_at_the_main_line_and_nested_depth() {
+        let mut record = tree();
+        assert_eq!(
+            record.promote_variation(&path(&[1, 0, 1, 1, 0])),
+            path(&[1, 0, 1, 0, 0])
+        );
+        assert_eq!(san_at(&record, &[1, 0, 1, 0, 0]).as_deref(), Some("c3"));
+        assert_eq!(san_at(&record, &[1, 0, 1, 1, 0]).as_deref(), Some("Nc3"));
+
+        assert_eq!(
+            record.promote_variation(&path(&[1, 0, 1, 1, 1])),
+            path(&[1, 0, 1, 0, 1])
+        );
+        assert_eq!(
+            record.promote_variation(&path(&[1, 0, 1, 0, 1])),
+            path(&[1, 0, 2])
+        );
+        assert_eq!(sans(&record.moves()[1].variations[0]), ["c5", "Nc3", "Nc6"]);
+        assert_eq!(
+            sans(&record.moves()[1].variations[0][1].variations[0]),
+            ["Nf3", "d6"]
+        );
+        assert_eq!(
+            sans(&record.moves()[1].variations[0][1].variations[1]),
+            ["c3"]
+        );
+
+        let original = tree();
+        assert_eq!(record.promote_variation(&path(&[1, 0, 0])), path(&[1]));
+        assert_eq!(sans(record.moves()), ["e4", "c5", "Nc3", "Nc6"]);
+        assert_eq!(sans(&record.moves()[1].variations[0]), ["e5", "Nf3", "Nc6"]);
+        assert_eq!(sans(&record.moves()[1].variations[1]), ["e6", "d4", "d5"]);
+        assert!(!record.same_mainline(&original));
+
+        assert_eq!(record.promote_variation(&path(&[2])), path(&[2]));
+    }
+
+    #[test]
+    fn demotes_lines_at_the_main_line_and_nested_depth() {
+        let mut record = tree();
+        assert_eq!(record.demote_variation(&path(&[1, 0, 0])), path(&[1, 1, 0]));
+        assert_eq!(sans(&record.moves()[1].variations[0]), ["e6", "d4", "d5"]);
+
+        assert_eq!(record.demote_variation(&path(&[3])), path(&[1, 0, 2]));
+        assert_eq!(sans(record.moves()), ["e4", "e6", "d4", "d5"]);
+        assert_eq!(sans(&record.moves()[1].variations[0]), ["e5", "Nf3", "Nc6"]);
+        assert_eq!(sans(&record.moves()[1].variations[1]), ["c5", "Nf3", "d6"]);
+    }
+
+    #[test]
+    fn demoting_the_last_variation_changes_nothing() {
+        let mut record = tree();
+        let pgn = record.to_pgn("*");
+        assert_eq!(record.demote_variation(&path(&[1, 1, 1])), path(&[1, 1, 1]));
+        assert_eq!(
+            record.demote_variation(&path(&[1, 0, 1, 1, 0])),
+            path(&[1, 0, 1, 1, 0])
+        );
+        assert_eq!(record.to_pgn("*"), pgn);
+    }
+
+    #[test]
+    fn deleting_a_variation_returns_the_position_it_branched_from() {
+        let mut record = tree();
+        assert_eq!(
+            record.delete_variation(&path(&[1, 0, 1, 0, 1])),
+            path(&[1, 0, 0])
+        );
+        assert_eq!(san_at(&record, &[1, 0, 1, 0, 0]).as_deref(), Some("c3"));
+        assert!(record.node(&path(&[1, 0, 1, 1, 0])).is_none());
+        assert!(record.position_at(&path(&[1, 0, 1, 1, 0])).is_none());
+
+        assert_eq!(record.delete_variation(&path(&[1, 0, 2])), path(&[0]));
+        assert_eq!(san_at(&record, &[1, 0, 0]).as_deref(), Some("e6"));
+        assert!(record.node(&path(&[1, 1, 0])).is_none());
+
+        let pgn = record.to_pgn("*");
+        assert_eq!(record.delete_variation(&path(&[2])), path(&[2]));
+        assert_eq!(record.to_pgn("*"), pgn);
+        assert!(record.same_mainline(&tree()));
+    }
+
+    #[test]
+    fn annotating_keeps_user_comments_and_assessments() {
+        let mut record =
+            GameRecord::from_pgn("1. e4 e5 2. Qh5!? {aggressive} Nc6 3. Bc4 Nf6 $14 *").unwrap();
+        record.annotate(2, 6, "Inaccuracy. Nf3 was best.");
+        record.annotate(5, 4, "Blunder. g6 was best.");
+        record.annotate(5, 4, "Blunder. g6 was best.");
+        assert_eq!(record.moves()[2].nags, [5]);
+        assert_eq!(
+            record.moves()[2].comment.as_deref(),
+            Some("aggressive Inaccuracy. Nf3 was best.")
+        );
+        assert_eq!(record.moves()[5].nags, [4, 14]);
+        assert_eq!(
+            record.moves()[5].comment.as_deref(),
+            Some("Blunder. g6 was best.")
+        );
+        assert!(record.moves()[4].nags.is_empty());
+        assert!(record.moves()[4].comment.is_none());
+    }
+
+    #[test]
+    fn nags_glyphs_and_comments_round_trip_through_pgn() {
+        let record = GameRecord::from_pgn(
+            "1. e4! $11 e5? $12 2. Nf3!! $13 Nc6?? $14 3. Bb5!? $15 a6?! $16 4. Ba4 $7 $17 \
+             Nf6 $8 $18 5. O-O $9 $19 Be7 = 6. Re1 +/= b5 -/+ 7. Bb3 ∞ {a plain { comment} \
+             d6 ; a line comment with } a brace\n8. c3 *",
+        )
+        .unwrap();
+        let expected_nags: [&[u8]; 12] = [
+            &[1, 11],
+            &[2, 12],
+            &[3, 13],
+            &[4, 14],
+            &[5, 15],
+            &[6, 16],
+            &[7, 17],
+            &[8, 18],
+            &[9, 19],
+            &[10],
+            &[14],
+            &[17],
+        ];
+        let nags = |record: &GameRecord| -> Vec<Vec<u8>> {
+            record
+                .moves()
+                .iter()
+                .map(|recorded_move| recorded_move.nags.clone())
+                .collect()
+        };
+        assert_eq!(nags(&record)[..12], expected_nags.map(<[u8]>::to_vec));
+        assert_eq!(record.moves()[12].nags, [13]);
+        assert_eq!(
+            record.moves()[12].comment.as_deref(),
+            Some("a plain { comment")
+        );
+        assert_eq!(
+            record.moves()[13].comment.as_deref(),
+            Some("a line comment with a brace")
+        );
+
+        let pgn = record.to_pgn("*");
+        let reread = GameRecord::from_pgn(&pgn).unwrap();
+        assert_eq!(nags(&reread), nags(&record));
+        assert_eq!(sans(reread.moves()), sans(record.moves()));
+        let comments = |record: &GameRecord| -> Vec<Option<String>> {
+            record
+                .moves()
+                .iter()
+                .map(|recorded_move| recorded_move.comment.clone())
+                .collect()
+        };
+        assert_eq!(comments(&reread), comments(&record));
+        assert_eq!(reread.to_pgn("*"), pgn);
+    }
+}
@@ -0,0 +1,176 @@
+use iced::futures::channel::mpsc;
+use iced::widget::{button, text, text_input, Column, Row};
+use iced::{Alignment, Color, Element, Subscription};
+use iced_chessboard::san::San;
+use pleco::{Board, Player};
+
+use super::Message;
+use crate::uci::{self, AnalysisCommand, AnalysisEvent, AnalysisInfo, Score};
+
+pub struct AnalysisPanel {
+    engine_path_input: String,
+    /// The path engines are started from, only updated when the input is submitted so that
+    /// typing does not start a process for every keystroke.
+    engine_path: String,
+    running: bool,
+    sender: Option<mpsc::Sender<AnalysisCommand>>,
+    engine_name: Option<String>,
+    info: Option<AnalysisInfo>,
+    error: Option<String>,
+}
+
+impl AnalysisPanel {
+    pub fn new() -> Self {
+        Self {
+            engine_path_input: String::new(),
+            engine_path: String::new(),
+            running: false,
+            sender: None,
+            engine_name: None,
+            info: None,
+            error: None,
+        }
+    }
+
+    pub fn engine_path(&self) -> &str {
+        &self.engine_path
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn score(&self) -> Option<Score> {
+        self.info.as_ref().and_then(|info| info.score)
+    }
+
+    pub fn set_engine_path(&mut self, engine_path: String) {
+        self.engine_path_input = engine_path;
+        self.load_engine_path();
+    }
+
+    pub fn edit_engine_path(&mut self, engine_path: String) {
+        self.engine_path_input = engine_path;
+    }
+
+    /// Uses the typed path from now on, restarting the analysis engine if it changed.
+    pub fn load_engine_path(&mut self) {
+        let engine_path = self.engine_path_input.trim();
+        if engine_path == self.engine_path {
+            return;
+        }
+        self.engine_path = engine_path.to_string();
+        self.running = self.running && !self.engine_path.is_empty();
+        self.reset_engine();
+    }
+
+    pub fn toggle(&mut self) {
+        self.load_engine_path();
+        self.running = !self.running && !self.engine_path.is_empty();
+        self.reset_engine();
+    }
+
+    fn reset_engine(&mut self) {
+        self.sender = None;
+        self.engine_name = None;
+        self.info = None;
+        self.error = None;
+    }
+
+    pub fn subscription(&self) -> Subscription<AnalysisEvent> {
+        if self.running {
+            uci::analysis(self.engine_path.clone())
+        } else {
+            Subscription::none()
+        }
+    }
+
+    /// Returns true when the engine has just become ready and must be given a position.
+    pub fn handle_event(&mut self, event: AnalysisEvent, current_fen: &str) -> bool {
+        match event {
+            AnalysisEvent::Ready(sender, engine_name) => {
+                self.sender = Some(sender);
+                self.engine_name = engine_name;
+                true
+            }
+            AnalysisEvent::Info(fen, mut info) => {
+                if fen == current_fen {
+                    let side_to_move = if fen.split_whitespace().nth(1) == Some("b") {
+                        Player::Black
+                    } else {
+                        Player::White
+                    };
+                    info.score = info
+                        .score
+                        .map(|score| score.white_point_of_view(side_to_move));
+                    self.info = Some(info);
+                }
+                false
+            }
+            AnalysisEvent::Failed(error) => {
+                self.sender = None;
+                self.error = Some(error);
+                false
+            }
+        }
+    }
+
+    pub fn analyze(&mut self, command: AnalysisCommand) {
+        self.info = None;
+        if let Some(ref mut sender) = self.sender {
+            if sender.try_send(command).is_err() {
+                self.error = Some(String::from("The engine is not responding"));
+            }
+        }
+    }
+
+    pub fn view(&self, board: &Board) -> Element<'_, Message> {
+        let toggle_label = if self.running {
+            "Stop analysis"
+        } else {
+            "Analyze"
+        };
+        let controls = Row::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(
+                text_input(
+                    "UCI engine executable",
+                    &self.engine_path_input,
+                    Message::EnginePathChanged,
+                )
+                .on_submit(Message::LoadEnginePath)
+                .width(200),
+            )
+            .push(button(text(toggle_label)).on_press(Message::ToggleAnalysis));
+
+        let mut column = Column::new().spacing(5).width(300).push(controls);
+
+        if let Some(ref error) = self.error {
+            column = column.push(text(error).style(Color::from_rgb8(0xC6, 0x28, 0x28)));
+        } else if self.running {
+            let engine_name = match self.engine_name {
+                Some(ref name) => name.clone(),
+                None if self.sender.is_none() => String::from("Starting engine..."),
+                None => String::from("Unknown engine"),
+            };
+            column = column.push(text(engine_name));
+
+            if let Some(ref info) = self.info {
+                let score = info
+                    .score
+                    .map(|score| score.to_string())
+                    .unwrap_or_else(|| String::from("?"));
+                let depth = info
+                    .depth
+                    .map(|depth| depth.to_string())
+                    .unwrap_or_else(|| String::from("?"));
+                column = column
+                    .push(text(format!("{}  (depth {})", score, depth)).size(24))
+                    .push(text(San::from_uci_line(board, &info.pv).join(" ")));
+            }
+        }
+
+        column.into()
+    }
+}
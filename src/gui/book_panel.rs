@@ -0,0 +1,126 @@
+use iced::widget::{button, text, text_input, Column, Row};
+use iced::{Alignment, Color, Element};
+use iced_chessboard::chess960::Chess960Castling;
+use iced_chessboard::position_setup::{CastlingRight, PositionSetup};
+use iced_chessboard::san::San;
+use pleco::Board;
+
+use super::Message;
+use crate::polyglot::PolyglotBook;
+
+pub struct BookPanel {
+    book_path: String,
+    book: Option<PolyglotBook>,
+    error: Option<String>,
+}
+
+impl BookPanel {
+    pub fn new() -> Self {
+        Self {
+            book_path: String::new(),
+            book: None,
+            error: None,
+        }
+    }
+
+    pub fn set_book_path(&mut self, book_path: String) {
+        self.book_path = book_path;
+    }
+
+    pub fn load(&mut self) {
+        let book_path = self.book_path.trim();
+        if book_path.is_empty() {
+            self.book = None;
+            self.error = None;
+            return;
+        }
+        match PolyglotBook::load(book_path) {
+            Ok(book) => {
+                self.book = Some(book);
+                self.error = None;
+            }
+            Err(err) => {
+                self.book = None;
+                self.error = Some(format!("Could not load {}: {}", book_path, err));
+            }
+        }
+    }
+
+    pub fn view(
+        &self,
+        board: &Board,
+        chess960_castling: Option<&Chess960Castling>,
+        accepts_moves: bool,
+    ) -> Element<'_, Message> {
+        let controls = Row::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(
+                text_input(
+                    "Polyglot book (.bin)",
+                    &self.book_path,
+                    Message::BookPathChanged,
+                )
+                .on_submit(Message::LoadBook)
+                .width(200),
+            )
+            .push(button(text("Load book")).on_press(Message::LoadBook));
+
+        let mut column = Column::new()
+            .spacing(5)
+            .width(300)
+            .push(text("Opening book").size(20))
+            .push(controls);
+
+        if let Some(ref error) = self.error {
+            return column
+                .push(text(error).style(Color::from_rgb8(0xC6, 0x28, 0x28)))
+                .into();
+        }
+        let book = match self.book {
+            Some(ref book) => book,
+            None => return column.into(),
+        };
+
+        let castling_rights = match chess960_castling {
+            Some(castling) => CastlingRight::ALL.map(|right| castling.rook_file(right).is_some()),
+            None => {
+                let setup = PositionSetup::from_board(board);
+                CastlingRight::ALL.map(|right| setup.can_castle(right))
+            }
+        };
+        let book_moves = book.moves(board, castling_rights, chess960_castling.is_some());
+        if book_moves.is_empty() {
+            return column.push(text("Out of book")).into();
+        }
+
+        let total_weight: u32 = book_moves
+            .iter()
+            .map(|book_move| book_move.weight as u32)
+            .sum();
+        column = book_moves.into_iter().fold(column, |column, book_move| {
+            let san = San::from_uci_line(board, std::slice::from_ref(&book_move.uci))
+                .pop()
+                .unwrap_or_default();
+            let share = if total_weight > 0 {
+                100.0 * book_move.weight as f64 / total_weight as f64
+            } else {
+                0.0
+            };
+            let mut move_button = button(text(san)).width(80);
+            if accepts_moves {
+                move_button = move_button.on_press(Message::PlayBookMove(book_move.uci));
+            }
+            column.push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .push(move_button)
+                    .push(text(format!("{:.1}%", share)).width(60))
+                    .push(text(format!("weight {}", book_move.weight)).size(16))
+                    .push(text(format!("learn {}", book_move.learn)).size(16)),
+            )
+        });
+        column.into()
+    }
+}
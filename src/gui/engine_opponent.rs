@@ -0,0 +1,356 @@
+use std::fmt;
+use std::time::Duration;
+
+use iced::futures::channel::mpsc;
+use iced::widget::{pick_list, text, text_input, Column, Row};
+use iced::{Alignment, Color, Element, Subscription};
+use pleco::Player;
+use serde::{Deserialize, Serialize};
+
+use super::Message;
+use crate::uci::{self, ClockTimes, OpponentCommand, OpponentEvent, SearchLimit};
+
+const DEFAULT_DEPTH: u32 = 10;
+const DEFAULT_MOVETIME_MS: u64 = 1000;
+const MAX_SKILL_LEVEL: u8 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OpponentChoice {
+    Peer,
+    ComputerPlaysBlack,
+    ComputerPlaysWhite,
+}
+
+impl OpponentChoice {
+    pub const ALL: [OpponentChoice; 3] = [
+        OpponentChoice::Peer,
+        OpponentChoice::ComputerPlaysBlack,
+        OpponentChoice::ComputerPlaysWhite,
+    ];
+
+    fn engine_color(self) -> Option<Player> {
+        match self {
+            OpponentChoice::Peer => None,
+            OpponentChoice::ComputerPlaysBlack => Some(Player::Black),
+            OpponentChoice::ComputerPlaysWhite => Some(Player::White),
+        }
+    }
+}
+
+impl fmt::Display for OpponentChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OpponentChoice::Peer => write!(f, "Peer"),
+            OpponentChoice::ComputerPlaysBlack => write!(f, "Computer (you play White)"),
+            OpponentChoice::ComputerPlaysWhite => write!(f, "Computer (you play Black)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchLimitKind {
+    Depth,
+    MoveTime,
+}
+
+impl SearchLimitKind {
+    pub const ALL: [SearchLimitKind; 2] = [SearchLimitKind::Depth, SearchLimitKind::MoveTime];
+}
+
+impl fmt::Display for SearchLimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SearchLimitKind::Depth => write!(f, "Depth"),
+            SearchLimitKind::MoveTime => write!(f, "Move time (ms)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpponentSettings {
+    pub choice: OpponentChoice,
+    pub limit_kind: SearchLimitKind,
+    pub limit_value: String,
+    pub skill_level: String,
+    #[serde(default)]
+    pub peer_name: String,
+}
+
+pub struct EngineOpponent {
+    choice: OpponentChoice,
+    limit_kind: SearchLimitKind,
+    limit_value: String,
+    skill_level: String,
+    peer_name: String,
+    sender: Option<mpsc::Sender<OpponentCommand>>,
+    engine_name: Option<String>,
+    thinking: bool,
+    error: Option<String>,
+}
+
+impl EngineOpponent {
+    pub fn new() -> Self {
+        Self {
+            choice: OpponentChoice::Peer,
+            limit_kind: SearchLimitKind::Depth,
+            limit_value: DEFAULT_DEPTH.to_string(),
+            skill_level: String::new(),
+            peer_name: String::new(),
+            sender: None,
+            engine_name: None,
+            thinking: false,
+            error: None,
+        }
+    }
+
+    pub fn engine_color(&self) -> Option<Player> {
+        self.choice.engine_color()
+    }
+
+    pub fn is_engine_turn(&self, turn: Player) -> bool {
+        self.engine_color() == Some(turn)
+    }
+
+    pub fn select(&mut self, choice: OpponentChoice) {
+        self.stop();
+        if choice.engine_color().is_none() {
+            self.sender = None;
+            self.engine_name = None;
+        }
+        self.choice = choice;
+        self.thinking = false;
+        self.error = None;
+    }
+
+    pub fn settings(&self) -> OpponentSettings {
+        OpponentSettings {
+            choice: self.choice,
+            limit_kind: self.limit_kind,
+            limit_value: self.limit_value.clone(),
+            skill_level: self.skill_level.clone(),
+            peer_name: self.peer_name.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, settings: OpponentSettings) {
+        self.select(settings.choice);
+        self.limit_kind = settings.limit_kind;
+        self.set_limit_value(settings.limit_value);
+        self.set_skill_level(settings.skill_level);
+        self.peer_name = settings.peer_name;
+    }
+
+    pub fn select_limit_kind(&mut self, limit_kind: SearchLimitKind) {
+        if limit_kind != self.limit_kind {
+            self.limit_kind = limit_kind;
+            self.limit_value = match limit_kind {
+                SearchLimitKind::Depth => DEFAULT_DEPTH.to_string(),
+                SearchLimitKind::MoveTime => DEFAULT_MOVETIME_MS.to_string(),
+            };
+        }
+    }
+
+    pub fn set_limit_value(&mut self, limit_value: String) {
+        if limit_value.chars().all(|digit| digit.is_ascii_digit()) && limit_value.len() <= 6 {
+            self.limit_value = limit_value;
+        }
+    }
+
+    pub fn peer_name(&self) -> &str {
+        &self.peer_name
+    }
+
+    pub fn set_peer_name(&mut self, peer_name: String) {
+        self.peer_name = peer_name;
+    }
+
+    pub fn set_skill_level(&mut self, skill_level: String) {
+        let in_range = skill_level
+            .parse::<u8>()
+            .map(|level| level <= MAX_SKILL_LEVEL)
+            .unwrap_or(skill_level.is_empty());
+        if in_range {
+            self.skill_level = skill_level;
+        }
+    }
+
+    pub fn subscription(&self, engine_path: &str) -> Subscription<OpponentEvent> {
+        if self.engine_color().is_some() && !engine_path.is_empty() {
+            uci::opponent(engine_path.to_string())
+        } else {
+            Subscription::none()
+        }
+    }
+
+    /// Returns the engine move, once it has answered for the current position.
+    pub fn handle_event(&mut self, event: OpponentEvent, current_fen: &str) -> Option<String> {
+        match event {
+            OpponentEvent::Ready(sender, engine_name) => {
+                self.sender = Some(sender);
+                self.engine_name = engine_name;
+                // A newly started engine is not searching yet.
+                self.thinking = false;
+                self.error = None;
+                None
+            }
+            OpponentEvent::BestMove(fen, best_move) => {
+                if fen != current_fen {
+                    return None;
+                }
+                self.thinking = false;
+                if best_move.is_none() {
+                    self.error = Some(String::from("The engine did not return any move"));
+                }
+                best_move
+            }
+            OpponentEvent::Failed(error) => {
+                self.sender = None;
+                self.thinking = false;
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+
+    pub fn reject_move(&mut self, engine_move: &str) {
+        self.error = Some(format!(
+            "The engine played an illegal move: {}",
+            engine_move
+        ));
+    }
+
+    /// Abandons the search in progress, whose move would no longer fit the game.
+    pub fn stop(&mut self) {
+        if !self.thinking {
+            return;
+        }
+        self.thinking = false;
+        if let Some(ref mut sender) = self.sender {
+            let _ = sender.try_send(OpponentCommand::Stop);
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    pub fn request_move(
+        &mut self,
+        start_fen: String,
+        moves: Vec<String>,
+        position_fen: String,
+        chess960: bool,
+        clock: Option<ClockTimes>,
+    ) {
+        if self.thinking {
+            return;
+        }
+        let limit = self.search_limit(clock);
+        let skill_level = self.skill_level.parse().ok();
+        if let Some(ref mut sender) = self.sender {
+            let command = OpponentCommand::Play {
+                start_fen,
+                moves,
+                position_fen,
+                chess960,
+                limit,
+                skill_level,
+                clock,
+            };
+            match sender.try_send(command) {
+                Ok(_) => self.thinking = true,
+                Err(_) => self.error = Some(String::from("The engine is not responding")),
+            }
+        }
+    }
+
+    pub fn view(&self, engine_path: &str) -> Element<'_, Message> {
+        let mut column = Column::new().spacing(5).push(
+            Row::new()
+                .spacing(5)
+                .align_items(Alignment::Center)
+                .push(text("Opponent"))
+                .push(pick_list(
+                    &OpponentChoice::ALL[..],
+                    Some(self.choice),
+                    Message::OpponentSelected,
+                )),
+        );
+
+        if self.engine_color().is_none() {
+            column = column.push(
+                text_input("Opponent name", &self.peer_name, Message::PeerNameChanged).width(200),
+            );
+        } else {
+            column = column.push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Alignment::Center)
+                    .push(pick_list(
+                        &SearchLimitKind::ALL[..],
+                        Some(self.limit_kind),
+                        Message::SearchLimitKindSelected,
+                    ))
+                    .push(
+                        text_input("", &self.limit_value, Message::SearchLimitValueChanged)
+                            .width(70),
+                    )
+                    .push(text("Skill level"))
+                    .push(
+                        text_input("0-20", &self.skill_level, Message::SkillLevelChanged).width(50),
+                    ),
+            );
+
+            let status = if let Some(ref error) = self.error {
+                text(error).style(Color::from_rgb8(0xC6, 0x28, 0x28))
+            } else if engine_path.is_empty() {
+                text("Enter the UCI engine executable in the analysis panel")
+                    .style(Color::from_rgb8(0xC6, 0x28, 0x28))
+            } else if self.sender.is_none() {
+                text("Starting engine...")
+            } else if self.thinking {
+                text(format!("{} is thinking...", self.engine_display_name()))
+            } else {
+                text(self.engine_display_name())
+            };
+            column = column.push(status);
+        }
+
+        column.into()
+    }
+
+    pub fn engine_display_name(&self) -> &str {
+        self.engine_name.as_deref().unwrap_or("Engine")
+    }
+
+    fn search_limit(&self, clock: Option<ClockTimes>) -> SearchLimit {
+        match self.limit_kind {
+            SearchLimitKind::Depth => SearchLimit::Depth(
+                self.limit_value
+                    .parse()
+                    .ok()
+                    .filter(|depth| *depth > 0)
+                    .unwrap_or(DEFAULT_DEPTH),
+            ),
+            SearchLimitKind::MoveTime => {
+                let movetime = Duration::from_millis(
+                    self.limit_value
+                        .parse()
+                        .ok()
+                        .filter(|movetime| *movetime > 0)
+                        .unwrap_or(DEFAULT_MOVETIME_MS),
+                );
+                let remaining = clock.and_then(|clock| {
+                    self.engine_color().map(|color| match color {
+                        Player::White => clock.white,
+                        Player::Black => clock.black,
+                    })
+                });
+                match remaining {
+                    Some(remaining) => SearchLimit::MoveTime(movetime.min(remaining / 10)),
+                    None => SearchLimit::MoveTime(movetime),
+                }
+            }
+        }
+    }
+}
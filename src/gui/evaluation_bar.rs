@@ -0,0 +1,169 @@
+use iced::{Color, Element, Font, Length, Point, Rectangle, Size};
+use iced_native::renderer::BorderRadius;
+use iced_native::text::Text;
+use iced_native::{layout, renderer, text, Widget};
+
+use crate::uci::Score;
+
+const WIN_PROBABILITY_FACTOR: f32 = 0.003_682_08;
+
+pub struct EvaluationBar {
+    width: u16,
+    height: u16,
+    white_share: f32,
+    label: String,
+    black_at_bottom: bool,
+    white_color: Color,
+    black_color: Color,
+    border_color: Color,
+}
+
+impl EvaluationBar {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            white_share: 0.5,
+            label: String::new(),
+            black_at_bottom: false,
+            white_color: Color::from_rgb8(0xF5, 0xF5, 0xF5),
+            black_color: Color::from_rgb8(0x40, 0x40, 0x40),
+            border_color: Color::from_rgb8(0x80, 0x80, 0x80),
+        }
+    }
+
+    pub fn set_white_share(&mut self, white_share: f32) {
+        self.white_share = white_share.clamp(0.0, 1.0);
+    }
+
+    pub fn set_label(&mut self, label: String) {
+        self.label = label;
+    }
+
+    pub fn set_orientation(&mut self, black_at_bottom: bool) {
+        self.black_at_bottom = black_at_bottom;
+    }
+
+    pub fn white_share_of(score: Score) -> f32 {
+        match score {
+            Score::Centipawns(centipawns) => {
+                let winning_chances =
+                    2.0 / (1.0 + (-WIN_PROBABILITY_FACTOR * centipawns as f32).exp()) - 1.0;
+                0.5 + 0.5 * winning_chances
+            }
+            Score::Mate(moves) if moves > 0 => 1.0,
+            Score::Mate(_) => 0.0,
+        }
+    }
+
+    pub fn label_of(score: Score) -> String {
+        match score {
+            Score::Centipawns(centipawns) => {
+                format!("{:.1}", centipawns.unsigned_abs() as f32 / 100.0)
+            }
+            Score::Mate(moves) => format!("M{}", moves.unsigned_abs()),
+        }
+    }
+}
+
+impl<Message, Renderer> Widget<Message, Renderer> for EvaluationBar
+where
+    Renderer: renderer::Renderer + text::Renderer<Font = Font>,
+{
+    fn width(&self) -> Length {
+        Length::Fixed(self.width as f32)
+    }
+
+    fn height(&self) -> Length {
+        Length::Fixed(self.height as f32)
+    }
+
+    fn layout(
+        &self,
+        _renderer: &Renderer,
+        _limits: &iced_native::layout::Limits,
+    ) -> iced_native::layout::Node {
+        layout::Node::new(Size::new(self.width as f32, self.height as f32))
+    }
+
+    fn draw(
+        &self,
+        _state: &iced_native::widget::Tree,
+        renderer: &mut Renderer,
+        _theme: &Renderer::Theme,
+        _style: &iced_native::renderer::Style,
+        layout: iced_native::Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let (top_color, bottom_color, bottom_share) = if self.black_at_bottom {
+            (self.white_color, self.black_color, 1.0 - self.white_share)
+        } else {
+            (self.black_color, self.white_color, self.white_share)
+        };
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds,
+                border_color: self.border_color,
+                border_width: 1f32,
+                border_radius: BorderRadius::default(),
+            },
+            top_color,
+        );
+
+        let bottom_height = bounds.height * bottom_share;
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle {
+                    x: bounds.x,
+                    y: bounds.y + bounds.height - bottom_height,
+                    width: bounds.width,
+                    height: bottom_height,
+                },
+                border_color: Color::TRANSPARENT,
+                border_width: 0f32,
+                border_radius: BorderRadius::default(),
+            },
+            bottom_color,
+        );
+
+        let font_size = bounds.width * 0.45;
+        let white_is_better = self.white_share >= 0.5;
+        let label_at_bottom = white_is_better != self.black_at_bottom;
+        let (label_y, label_color) = if label_at_bottom {
+            (bounds.y + bounds.height - font_size, bottom_color)
+        } else {
+            (bounds.y + font_size, top_color)
+        };
+        let label_color = if label_color == self.white_color {
+            self.black_color
+        } else {
+            self.white_color
+        };
+        renderer.fill_text(Text {
+            content: &self.label,
+            bounds: Rectangle {
+                x: bounds.center_x(),
+                y: label_y,
+                width: bounds.width,
+                height: font_size,
+            },
+            color: label_color,
+            size: font_size,
+            font: Font::default(),
+            horizontal_alignment: iced::alignment::Horizontal::Center,
+            vertical_alignment: iced::alignment::Vertical::Center,
+        });
+    }
+}
+
+impl<'a, Message: 'a, Renderer> From<EvaluationBar> for Element<'a, Message, Renderer>
+where
+    Renderer: renderer::Renderer + text::Renderer<Font = Font>,
+{
+    fn from(bar: EvaluationBar) -> Self {
+        Self::new(bar)
+    }
+}
@@ -0,0 +1,330 @@
+use std::fmt;
+
+use iced::widget::{button, pick_list, scrollable, text, text_input, Column, Row};
+use iced::{Alignment, Color, Element};
+use pleco::Board;
+
+use super::session::Session;
+use super::Message;
+use crate::game_database::{GameDatabase, StoredGame};
+use crate::position_search::{PatternQuery, PositionIndex, PositionQuery, SearchResults};
+
+const DATABASE_FILE_NAME: &str = "games.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultFilter {
+    Any,
+    WhiteWins,
+    BlackWins,
+    Draw,
+    Unfinished,
+}
+
+impl ResultFilter {
+    pub const ALL: [ResultFilter; 5] = [
+        ResultFilter::Any,
+        ResultFilter::WhiteWins,
+        ResultFilter::BlackWins,
+        ResultFilter::Draw,
+        ResultFilter::Unfinished,
+    ];
+
+    fn result(self) -> Option<&'static str> {
+        match self {
+            ResultFilter::Any => None,
+            ResultFilter::WhiteWins => Some("1-0"),
+            ResultFilter::BlackWins => Some("0-1"),
+            ResultFilter::Draw => Some("1/2-1/2"),
+            ResultFilter::Unfinished => Some("*"),
+        }
+    }
+}
+
+impl fmt::Display for ResultFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResultFilter::Any => write!(f, "Any result"),
+            ResultFilter::WhiteWins => write!(f, "1-0"),
+            ResultFilter::BlackWins => write!(f, "0-1"),
+            ResultFilter::Draw => write!(f, "1/2-1/2"),
+            ResultFilter::Unfinished => write!(f, "*"),
+        }
+    }
+}
+
+pub struct GameBrowser {
+    database: Option<GameDatabase>,
+    error: Option<String>,
+    visible: bool,
+    opponent_filter: String,
+    result_filter: ResultFilter,
+    opening_filter: String,
+    index: Option<PositionIndex>,
+    indexing: bool,
+    pending_search: Option<(PositionQuery, String)>,
+    pattern: String,
+    search: Option<(String, SearchResults)>,
+    search_error: Option<String>,
+}
+
+impl GameBrowser {
+    pub fn new() -> Self {
+        let database = Session::data_dir()
+            .ok_or_else(|| String::from("No data directory is available for this user"))
+            .and_then(|data_dir| {
+                GameDatabase::open(data_dir.join(DATABASE_FILE_NAME))
+                    .map_err(|err| format!("Could not open the game database: {}", err))
+            });
+        let (database, error) = match database {
+            Ok(database) => (Some(database), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            database,
+            error,
+            visible: false,
+            opponent_filter: String::new(),
+            result_filter: ResultFilter::Any,
+            opening_filter: String::new(),
+            index: None,
+            indexing: false,
+            pending_search: None,
+            pattern: String::new(),
+            search: None,
+            search_error: None,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    pub fn set_opponent_filter(&mut self, opponent_filter: String) {
+        self.opponent_filter = opponent_filter;
+    }
+
+    pub fn set_result_filter(&mut self, result_filter: ResultFilter) {
+        self.result_filter = result_filter;
+    }
+
+    pub fn set_opening_filter(&mut self, opening_filter: String) {
+        self.opening_filter = opening_filter;
+    }
+
+    pub fn set_pattern(&mut self, pattern: String) {
+        self.pattern = pattern;
+    }
+
+    pub fn add(&mut self, game: StoredGame) {
+        if let Some(ref mut database) = self.database {
+            if let Some(ref mut index) = self.index {
+                index.add(&game);
+            }
+            if let Err(err) = database.add(game) {
+                self.index = None;
+                self.error = Some(format!("Could not store the game: {}", err));
+            }
+        }
+    }
+
+    /// Searches for the position, or returns the games to index first when no index exists yet.
+    pub fn search_position(&mut self, board: &Board) -> Option<Vec<StoredGame>> {
+        self.run_search(
+            PositionQuery::Position(board.zobrist()),
+            String::from("reached the current position"),
+        )
+    }
+
+    pub fn search_pattern(&mut self) -> Option<Vec<StoredGame>> {
+        match PatternQuery::parse(&self.pattern) {
+            Ok(pattern) => self.run_search(
+                PositionQuery::Pattern(pattern),
+                format!("matched {}", self.pattern.trim()),
+            ),
+            Err(error) => {
+                self.search = None;
+                self.search_error = Some(error);
+                None
+            }
+        }
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search = None;
+        self.search_error = None;
+        self.pending_search = None;
+    }
+
+    /// Takes the index built in the background and runs the search that was waiting for it.
+    pub fn set_index(&mut self, index: Option<PositionIndex>) {
+        self.indexing = false;
+        let (database, mut index) = match (self.database.as_ref(), index) {
+            (Some(database), Some(index)) => (database, index),
+            _ => {
+                self.pending_search = None;
+                self.search_error = Some(String::from("Could not index the stored games"));
+                return;
+            }
+        };
+        // Games stored while the index was being built are added now.
+        database.games()[index.len()..]
+            .iter()
+            .for_each(|game| index.add(game));
+        self.index = Some(index);
+        if let Some((query, description)) = self.pending_search.take() {
+            self.run_search(query, description);
+        }
+    }
+
+    fn run_search(&mut self, query: PositionQuery, description: String) -> Option<Vec<StoredGame>> {
+        let database = self.database.as_ref()?;
+        self.search_error = None;
+        let index = match self.index {
+            Some(ref index) => index,
+            None => {
+                self.search = None;
+                self.pending_search = Some((query, description));
+                if self.indexing {
+                    return None;
+                }
+                self.indexing = true;
+                return Some(database.games().to_vec());
+            }
+        };
+        self.search = Some((description, index.search(database.games(), &query)));
+        None
+    }
+
+    /// Hides the browser and returns the PGN of the game to reopen.
+    pub fn open(&mut self, index: usize) -> Option<String> {
+        let game = self.database.as_ref()?.games().get(index)?;
+        self.visible = false;
+        Some(game.pgn.clone())
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let filters = Row::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(
+                text_input(
+                    "Opponent",
+                    &self.opponent_filter,
+                    Message::BrowserOpponentChanged,
+                )
+                .width(200),
+            )
+            .push(pick_list(
+                &ResultFilter::ALL[..],
+                Some(self.result_filter),
+                Message::BrowserResultSelected,
+            ))
+            .push(
+                text_input(
+                    "Opening",
+                    &self.opening_filter,
+                    Message::BrowserOpeningChanged,
+                )
+                .width(200),
+            )
+            .push(button(text("Close")).on_press(Message::ToggleGameBrowser));
+
+        let mut search_row = Row::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(button(text("Current position")).on_press(Message::SearchCurrentPosition))
+            .push(
+                text_input(
+                    "Material or pieces, e.g. KRPvKR or Ne5 pd6",
+                    &self.pattern,
+                    Message::BrowserPatternChanged,
+                )
+                .on_submit(Message::SearchPattern)
+                .width(350),
+            )
+            .push(button(text("Search")).on_press(Message::SearchPattern));
+        if self.search.is_some() || self.search_error.is_some() {
+            search_row =
+                search_row.push(button(text("Clear search")).on_press(Message::ClearSearch));
+        }
+
+        let mut column = Column::new()
+            .spacing(10)
+            .width(900)
+            .push(text("Game database").size(20))
+            .push(filters)
+            .push(search_row);
+        if self.pending_search.is_some() {
+            column = column.push(text("Indexing the stored games..."));
+        }
+        if let Some(ref search_error) = self.search_error {
+            column = column.push(text(search_error).style(Color::from_rgb8(0xC6, 0x28, 0x28)));
+        }
+
+        if let Some(ref error) = self.error {
+            column = column.push(text(error).style(Color::from_rgb8(0xC6, 0x28, 0x28)));
+        }
+        let database = match self.database {
+            Some(ref database) => database,
+            None => return column.into(),
+        };
+
+        let mut matches = database.search(
+            &self.opponent_filter,
+            self.result_filter.result(),
+            &self.opening_filter,
+        );
+        if let Some((ref description, ref results)) = self.search {
+            matches.retain(|index| results.games.contains(index));
+            column = column.push(text(format!(
+                "{} games {}",
+                results.games.len(),
+                description
+            )));
+            column = results.moves.iter().fold(column, |column, statistics| {
+                column.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(text(&statistics.san).width(80))
+                        .push(text(format!("{} games", statistics.games)).width(100))
+                        .push(
+                            text(format!(
+                                "+{} ={} -{}",
+                                statistics.white_wins, statistics.draws, statistics.black_wins
+                            ))
+                            .width(120),
+                        )
+                        .push(text(format!("{:.0}% for White", statistics.white_score()))),
+                )
+            });
+        }
+        if matches.is_empty() {
+            return column.push(text("No stored game matches")).into();
+        }
+        let rows = matches
+            .into_iter()
+            .fold(Column::new().spacing(5), |rows, index| {
+                let game = &database.games()[index];
+                rows.push(
+                    Row::new()
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .push(text(&game.date).width(100))
+                        .push(text(&game.opponent).width(180))
+                        .push(text(&game.result).width(70))
+                        .push(text(game.time_control.as_deref().unwrap_or("-")).width(60))
+                        .push(
+                            text(game.opening.as_deref().unwrap_or_default())
+                                .size(16)
+                                .width(350),
+                        )
+                        .push(button(text("Open")).on_press(Message::OpenStoredGame(index))),
+                )
+            });
+        column.push(scrollable(rows).height(400)).into()
+    }
+}
@@ -1,60 +1,810 @@
-mod chessboard;
-use chessboard::ChessBoard;
+mod analysis_panel;
+use analysis_panel::AnalysisPanel;
 
-use iced::theme::Theme;
-use iced::widget::{button, container, svg, Column};
+mod book_panel;
+use book_panel::BookPanel;
+
+mod engine_opponent;
+use engine_opponent::{EngineOpponent, OpponentChoice, SearchLimitKind};
+
+mod evaluation_bar;
+use evaluation_bar::EvaluationBar;
+
+mod game_browser;
+use game_browser::{GameBrowser, ResultFilter};
+
+mod move_list;
+use move_list::MoveList;
+
+mod replay;
+use replay::{Replay, ReplaySpeed};
+
+mod review_panel;
+use review_panel::ReviewPanel;
+
+mod session;
+use session::{SavedClock, Session};
+
+mod settings_panel;
+use settings_panel::SettingsPanel;
+
+mod tablebase_panel;
+use tablebase_panel::TablebasePanel;
+
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use iced::event::{self, Event};
+use iced::keyboard::{self, KeyCode};
+use iced::theme::{self, Theme};
+use iced::widget::{
+    button, checkbox, container, pick_list, radio, svg, text, text_input, Column, Row,
+};
 use iced::Element;
 use iced::Length;
-use iced::{Alignment, Application, Command};
-use pleco::Board;
+use iced::{Alignment, Application, Color, Command, Subscription};
+use iced_chessboard::chess960::{self, Chess960, Chess960Castling};
+use iced_chessboard::position_setup::{CastlingRight, PositionSetup, SetupError};
+use iced_chessboard::style::{Appearance, BoardStyle, StyleSheet};
+use iced_chessboard::{ChessBoard, GameStatus, MovableSides, MoveEvent, Moves, PiecesVectors};
+use pleco::{Board, File, Piece, Player, SQ};
+
+use crate::config::{BoardColorKind, BoardColors, Config, RgbColor, ThemeChoice};
+use crate::eco::{EcoTable, Opening};
+use crate::game_clock::{GameClock, TimeControl};
+use crate::game_controller::GameController;
+use crate::game_database::StoredGame;
+use crate::game_record::{GameRecord, MovePath, RecordedMove};
+use crate::hint::{self, Hint};
+use crate::pgn::Pgn;
+use crate::position_search::PositionIndex;
+use crate::post_game::GameReview;
+use crate::uci::{AnalysisCommand, AnalysisEvent, ClockTimes, OpponentEvent};
+
+const CLOCK_TICK: Duration = Duration::from_millis(100);
+const EVALUATION_ANIMATION_TICK: Duration = Duration::from_millis(16);
 
 #[derive(Debug, Clone)]
 pub enum Message {
     ToggleBoardOrientation,
-    UpdateBoardPosition(String),
+    BoardMove(MoveEvent),
+    EnterSetupMode,
+    CancelSetup,
+    SelectSetupPiece(Piece),
+    SetupCellPressed(SQ),
+    SetupWhiteToMove(bool),
+    SetupCastlingToggled(CastlingRight, bool),
+    SetupEnPassantSelected(EnPassantChoice),
+    ClearSetupBoard,
+    ResetSetupBoard,
+    StartGameFromSetup,
+    CopyFen,
+    PasteFen,
+    FenPasted(Option<String>),
+    DismissFenError,
+    NewGame,
+    Chess960NumberChanged(String),
+    NewChess960Game,
+    NewRandomChess960Game,
+    CopyPgn,
+    EnginePathChanged(String),
+    LoadEnginePath,
+    ToggleAnalysis,
+    Analysis(AnalysisEvent),
+    TimeControlSelected(TimeControlChoice),
+    ClockTick,
+    OpponentSelected(OpponentChoice),
+    SearchLimitKindSelected(SearchLimitKind),
+    SearchLimitValueChanged(String),
+    SkillLevelChanged(String),
+    PeerNameChanged(String),
+    Opponent(OpponentEvent),
+    RatedToggled(bool),
+    EvaluationAnimationTick,
+    RequestHint,
+    HintFound(String, Option<(SQ, SQ)>),
+    ReviewGame,
+    ReviewFinished(String, Result<GameReview, String>),
+    BookPathChanged(String),
+    LoadBook,
+    PlayBookMove(String),
+    TablebasePathChanged(String),
+    LoadTablebases,
+    PastePgn,
+    PgnPasted(Option<String>),
+    DismissPgnError,
+    ReplayFirst,
+    ReplayPrevious,
+    ReplayNext,
+    ReplayLast,
+    ToggleAutoplay,
+    ReplaySpeedSelected(ReplaySpeed),
+    AutoplayTick,
+    SelectMove(MovePath),
+    PromoteVariation,
+    DemoteVariation,
+    DeleteVariation,
+    CommentChanged(String),
+    ToggleNag(u8),
+    ResumeSession,
+    DiscardSession,
+    DismissSessionError,
+    ToggleGameBrowser,
+    BrowserOpponentChanged(String),
+    BrowserResultSelected(ResultFilter),
+    BrowserOpeningChanged(String),
+    OpenStoredGame(usize),
+    SearchCurrentPosition,
+    PositionIndexBuilt(Option<PositionIndex>),
+    BrowserPatternChanged(String),
+    SearchPattern,
+    ClearSearch,
+    ToggleSettings,
+    BoardSizeSelected(u16),
+    ThemeSelected(ThemeChoice),
+    AntialiasingToggled(bool),
+    BoardColorChanged(BoardColorKind, String),
+    ResetSettings,
+    DismissStartupError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnPassantChoice(Option<File>);
+
+impl EnPassantChoice {
+    const ALL: [EnPassantChoice; 9] = [
+        EnPassantChoice(None),
+        EnPassantChoice(Some(File::A)),
+        EnPassantChoice(Some(File::B)),
+        EnPassantChoice(Some(File::C)),
+        EnPassantChoice(Some(File::D)),
+        EnPassantChoice(Some(File::E)),
+        EnPassantChoice(Some(File::F)),
+        EnPassantChoice(Some(File::G)),
+        EnPassantChoice(Some(File::H)),
+    ];
+}
+
+impl fmt::Display for EnPassantChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(file) => write!(f, "{}", (b'a' + file as u8) as char),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControlChoice(Option<TimeControl>);
+
+impl TimeControlChoice {
+    const ALL: [TimeControlChoice; 6] = [
+        TimeControlChoice(None),
+        TimeControlChoice(Some(TimeControl::minutes(1, 0))),
+        TimeControlChoice(Some(TimeControl::minutes(3, 2))),
+        TimeControlChoice(Some(TimeControl::minutes(5, 0))),
+        TimeControlChoice(Some(TimeControl::minutes(10, 5))),
+        TimeControlChoice(Some(TimeControl::minutes(15, 10))),
+    ];
+}
+
+impl fmt::Display for TimeControlChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(time_control) => write!(f, "{}", time_control),
+            None => write!(f, "No clock"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Flags {
+    pub config: Config,
+    /// The file given with `--config`, which settings are saved back to.
+    pub config_path: Option<PathBuf>,
+    pub startup_error: Option<String>,
+    pub launch: LaunchOptions,
+}
+
+#[derive(Debug, Default)]
+pub struct LaunchOptions {
+    pub fen: Option<String>,
+    pub pgn_path: Option<PathBuf>,
+    pub play_black: Option<bool>,
+    pub time_control: Option<TimeControl>,
 }
 
 pub struct App {
-    game: Board,
+    game: GameController,
+    chess960_number: String,
     black_at_bottom: bool,
+    setup: Option<PositionSetup>,
+    setup_piece: Piece,
+    fen_error: Option<String>,
+    pgn_error: Option<String>,
+    analysis: AnalysisPanel,
+    time_control: Option<TimeControl>,
+    clock: Option<GameClock>,
+    opponent: EngineOpponent,
+    rated: bool,
+    displayed_evaluation: f32,
+    hints_left: u8,
+    hint_pending: bool,
+    hint_arrow: Option<(SQ, SQ)>,
+    review: ReviewPanel,
+    book: BookPanel,
+    opening: Option<&'static Opening>,
+    tablebase: TablebasePanel,
+    replay: Replay,
+    saved_session: Option<Session>,
+    session_error: Option<String>,
+    browser: GameBrowser,
+    config: Config,
+    startup_error: Option<String>,
+    settings: SettingsPanel,
 }
 
 impl Application for App {
     type Message = Message;
     type Executor = iced::executor::Default;
     type Theme = iced::Theme;
-    type Flags = ();
+    type Flags = Flags;
 
-    fn new(_flags: ()) -> (Self, Command<Message>) {
-        (
-            Self {
-                game: Board::default(),
-                black_at_bottom: false,
-            },
-            Command::none(),
-        )
+    fn new(flags: Flags) -> (Self, Command<Message>) {
+        let (saved_session, session_error) = match Session::load() {
+            Ok(saved_session) => (saved_session, None),
+            Err(err) => (
+                None,
+                Some(format!("The saved game could not be read: {}", err)),
+            ),
+        };
+        let mut app = Self {
+            game: GameController::new(Board::default(), None),
+            chess960_number: String::new(),
+            black_at_bottom: false,
+            setup: None,
+            setup_piece: Piece::WhitePawn,
+            fen_error: None,
+            pgn_error: None,
+            analysis: AnalysisPanel::new(),
+            time_control: None,
+            clock: None,
+            opponent: EngineOpponent::new(),
+            rated: false,
+            displayed_evaluation: 0.5,
+            hints_left: hint::HINTS_PER_GAME,
+            hint_pending: false,
+            hint_arrow: None,
+            review: ReviewPanel::new(),
+            book: BookPanel::new(),
+            opening: None,
+            tablebase: TablebasePanel::new(&flags.config.tablebase_directory),
+            replay: Replay::new(),
+            saved_session,
+            session_error,
+            browser: GameBrowser::new(),
+            settings: SettingsPanel::new(&flags.config, flags.config_path),
+            config: flags.config,
+            startup_error: flags.startup_error,
+        };
+        app.apply_launch_options(flags.launch);
+        (app, Command::none())
     }
 
     fn title(&self) -> String {
-        String::from("Peer chess")
+        match self.opening {
+            Some(opening) => format!("Peer chess - {}", opening),
+            None => String::from("Peer chess"),
+        }
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::ToggleBoardOrientation => {
-                self.black_at_bottom = ! self.black_at_bottom;
+                self.black_at_bottom = !self.black_at_bottom;
+                self.autosave();
                 Command::none()
-            },
-            Message::UpdateBoardPosition(new_fen) => {
-                if let Ok(new_logic) =  Board::from_fen(&new_fen) {
-                    self.game = new_logic;
+            }
+            Message::BoardMove(move_event) => {
+                if let Some(cursor) = self.replay.cursor().cloned() {
+                    self.add_variation_move(&cursor, move_event);
+                    return Command::none();
+                }
+                if !self.accepts_user_moves() {
+                    return Command::none();
+                }
+                match self.game.play_uci_move(&move_event.uci) {
+                    Some(_) => self.after_move(),
+                    None => Command::none(),
+                }
+            }
+            Message::EnterSetupMode => {
+                self.setup = Some(PositionSetup::from_board(self.game.board()));
+                Command::none()
+            }
+            Message::CancelSetup => {
+                self.setup = None;
+                Command::none()
+            }
+            Message::SelectSetupPiece(piece) => {
+                self.setup_piece = piece;
+                Command::none()
+            }
+            Message::SetupCellPressed(square) => {
+                if let Some(ref mut setup) = self.setup {
+                    let new_piece = if setup.piece_at_sq(square) == self.setup_piece {
+                        Piece::None
+                    } else {
+                        self.setup_piece
+                    };
+                    setup.set_piece_at_sq(square, new_piece);
+                }
+                Command::none()
+            }
+            Message::SetupWhiteToMove(white_to_move) => {
+                if let Some(ref mut setup) = self.setup {
+                    setup.set_turn(if white_to_move {
+                        Player::White
+                    } else {
+                        Player::Black
+                    });
+                }
+                Command::none()
+            }
+            Message::SetupCastlingToggled(right, enabled) => {
+                if let Some(ref mut setup) = self.setup {
+                    setup.set_castling(right, enabled);
+                }
+                Command::none()
+            }
+            Message::SetupEnPassantSelected(choice) => {
+                if let Some(ref mut setup) = self.setup {
+                    setup.set_en_passant_file(choice.0);
+                }
+                Command::none()
+            }
+            Message::ClearSetupBoard => {
+                self.setup = Some(PositionSetup::empty());
+                Command::none()
+            }
+            Message::ResetSetupBoard => {
+                self.setup = Some(PositionSetup::from_board(&Board::start_pos()));
+                Command::none()
+            }
+            Message::StartGameFromSetup => {
+                if let Some(ref setup) = self.setup {
+                    if let Ok(new_logic) = setup.validate() {
+                        self.start_game(new_logic, None);
+                        self.setup = None;
+                    }
+                }
+                Command::none()
+            }
+            Message::CopyFen => {
+                let fen = match self.setup {
+                    Some(ref setup) => setup.fen(),
+                    None => self.current_fen(),
+                };
+                iced::clipboard::write(fen)
+            }
+            Message::PasteFen => iced::clipboard::read(Message::FenPasted),
+            Message::FenPasted(content) => {
+                let fen = content.unwrap_or_default();
+                let fen = fen.trim();
+                if fen.is_empty() {
+                    self.fen_error = Some(String::from("The clipboard does not contain any text"));
+                    return Command::none();
+                }
+                if self.setup.is_some() {
+                    match PositionSetup::from_fen(fen) {
+                        Ok(setup) => {
+                            self.setup = Some(setup);
+                            self.fen_error = None;
+                        }
+                        Err(err) => self.fen_error = Some(err.to_string()),
+                    }
+                } else {
+                    self.fen_error = self
+                        .start_game_from_fen(fen)
+                        .err()
+                        .map(|err| err.to_string());
+                }
+                Command::none()
+            }
+            Message::DismissFenError => {
+                self.fen_error = None;
+                Command::none()
+            }
+            Message::NewGame => {
+                self.start_game(Board::start_pos(), None);
+                Command::none()
+            }
+            Message::Chess960NumberChanged(number) => {
+                if number.chars().all(|digit| digit.is_ascii_digit()) && number.len() <= 3 {
+                    self.chess960_number = number;
+                }
+                Command::none()
+            }
+            Message::NewChess960Game => {
+                match self.chess960_number.parse::<u16>() {
+                    Ok(number) if number < chess960::POSITIONS_COUNT => {
+                        let (new_logic, castling) = Chess960::start_position(number);
+                        self.start_game(new_logic, Some(castling));
+                    }
+                    _ => {
+                        self.fen_error = Some(format!(
+                            "Chess960 positions are numbered from 0 to {}",
+                            chess960::POSITIONS_COUNT - 1
+                        ));
+                    }
+                }
+                Command::none()
+            }
+            Message::NewRandomChess960Game => {
+                let number = Chess960::random_position_number();
+                self.chess960_number = number.to_string();
+                let (new_logic, castling) = Chess960::start_position(number);
+                self.start_game(new_logic, Some(castling));
+                Command::none()
+            }
+            Message::CopyPgn => iced::clipboard::write(self.game.record().to_pgn(self.result())),
+            Message::EnginePathChanged(engine_path) => {
+                self.analysis.edit_engine_path(engine_path);
+                Command::none()
+            }
+            Message::LoadEnginePath => {
+                self.analysis.load_engine_path();
+                Command::none()
+            }
+            Message::ToggleAnalysis => {
+                if self.is_rated_peer_game() {
+                    return Command::none();
+                }
+                self.analysis.toggle();
+                Command::none()
+            }
+            Message::Analysis(event) => {
+                let current_fen = self.current_fen();
+                if self.analysis.handle_event(event, &current_fen) {
+                    self.request_analysis();
+                }
+                Command::none()
+            }
+            Message::TimeControlSelected(choice) => {
+                self.time_control = choice.0;
+                Command::none()
+            }
+            Message::ClockTick => {
+                let flagged = match self.clock {
+                    Some(ref mut clock) => clock.flagged().is_none() && clock.tick().is_some(),
+                    None => false,
+                };
+                if flagged {
+                    self.autosave();
+                    self.store_finished_game();
+                    self.analysis.analyze(AnalysisCommand::Stop);
+                    return self.review_game();
+                }
+                Command::none()
+            }
+            Message::OpponentSelected(choice) => {
+                self.opponent.select(choice);
+                self.game.set_movable_sides(self.movable_sides());
+                if let Some(engine_color) = self.opponent.engine_color() {
+                    self.black_at_bottom = engine_color == Player::White;
+                }
+                if self.is_rated_peer_game() && self.analysis.is_running() {
+                    self.analysis.toggle();
+                }
+                self.request_engine_move();
+                self.autosave();
+                Command::none()
+            }
+            Message::SearchLimitKindSelected(limit_kind) => {
+                self.opponent.select_limit_kind(limit_kind);
+                Command::none()
+            }
+            Message::SearchLimitValueChanged(limit_value) => {
+                self.opponent.set_limit_value(limit_value);
+                Command::none()
+            }
+            Message::SkillLevelChanged(skill_level) => {
+                self.opponent.set_skill_level(skill_level);
+                Command::none()
+            }
+            Message::PeerNameChanged(peer_name) => {
+                self.opponent.set_peer_name(peer_name);
+                Command::none()
+            }
+            Message::RatedToggled(rated) => {
+                self.rated = rated;
+                if self.is_rated_peer_game() && self.analysis.is_running() {
+                    self.analysis.toggle();
+                }
+                Command::none()
+            }
+            Message::EvaluationAnimationTick => {
+                let target = self.target_evaluation();
+                let step = (target - self.displayed_evaluation) * 0.2;
+                self.displayed_evaluation = if step.abs() < 0.001 {
+                    target
+                } else {
+                    self.displayed_evaluation + step
+                };
+                Command::none()
+            }
+            Message::RequestHint => {
+                if !self.can_request_hint() {
+                    return Command::none();
+                }
+                self.hints_left -= 1;
+                self.hint_pending = true;
+                let fen = self.current_fen();
+                Command::perform(Hint::search(self.game.board().clone()), move |hint| {
+                    Message::HintFound(fen.clone(), hint)
+                })
+            }
+            Message::HintFound(fen, hint) => {
+                self.hint_pending = false;
+                if fen == self.current_fen() {
+                    self.hint_arrow = hint;
+                }
+                Command::none()
+            }
+            Message::Opponent(event) => {
+                let current_fen = self.current_fen();
+                let engine_started = matches!(event, OpponentEvent::Ready(..));
+                let engine_move = self.opponent.handle_event(event, &current_fen);
+                match engine_move {
+                    Some(engine_move) => {
+                        if self.is_game_over() {
+                            return Command::none();
+                        }
+                        match self.game.play_uci_move(&engine_move) {
+                            Some(_) => self.after_move(),
+                            None => {
+                                self.opponent.reject_move(&engine_move);
+                                Command::none()
+                            }
+                        }
+                    }
+                    None => {
+                        if engine_started {
+                            self.request_engine_move();
+                        }
+                        Command::none()
+                    }
+                }
+            }
+            Message::ReviewGame => self.review_game(),
+            Message::ReviewFinished(game_key, result) => {
+                if game_key == self.game_key() {
+                    self.review.finish(result, self.game.record_mut());
+                }
+                Command::none()
+            }
+            Message::BookPathChanged(book_path) => {
+                self.book.set_book_path(book_path);
+                Command::none()
+            }
+            Message::LoadBook => {
+                self.book.load();
+                Command::none()
+            }
+            Message::PlayBookMove(book_move) => {
+                if !self.accepts_user_moves() {
+                    return Command::none();
+                }
+                match self.game.play_uci_move(&book_move) {
+                    Some(_) => self.after_move(),
+                    None => Command::none(),
+                }
+            }
+            Message::TablebasePathChanged(directory_path) => {
+                self.tablebase.set_directory_path(directory_path);
+                Command::none()
+            }
+            Message::LoadTablebases => {
+                if self.tablebase.load(self.game.board()) {
+                    self.config.tablebase_directory = self.tablebase.directory_path().to_string();
+                    self.settings.save(&self.config);
+                }
+                Command::none()
+            }
+            Message::PastePgn => iced::clipboard::read(Message::PgnPasted),
+            Message::PgnPasted(content) => {
+                let pgn = content.unwrap_or_default();
+                if pgn.trim().is_empty() {
+                    self.pgn_error = Some(String::from("The clipboard does not contain any text"));
+                    return Command::none();
+                }
+                self.pgn_error = self.import_pgn(&pgn).err();
+                Command::none()
+            }
+            Message::DismissPgnError => {
+                self.pgn_error = None;
+                Command::none()
+            }
+            Message::ReplayFirst => {
+                self.replay.first(self.game.record());
+                Command::none()
+            }
+            Message::ReplayPrevious => {
+                self.replay.previous(self.game.record());
+                Command::none()
+            }
+            Message::ReplayNext | Message::AutoplayTick => {
+                self.replay.next(self.game.record());
+                Command::none()
+            }
+            Message::ReplayLast => {
+                self.replay.last();
+                Command::none()
+            }
+            Message::ToggleAutoplay => {
+                self.replay.toggle_autoplay(self.game.record());
+                Command::none()
+            }
+            Message::ReplaySpeedSelected(speed) => {
+                self.replay.set_speed(speed);
+                Command::none()
+            }
+            Message::SelectMove(path) => {
+                self.replay.select(path, self.game.record());
+                Command::none()
+            }
+            Message::PromoteVariation | Message::DemoteVariation | Message::DeleteVariation => {
+                if self.is_rated_peer_game() {
+                    return Command::none();
+                }
+                let selected = self.selected_path();
+                let mut record = self.game.record().clone();
+                let new_path = match message {
+                    Message::PromoteVariation => record.promote_variation(&selected),
+                    Message::DemoteVariation => record.demote_variation(&selected),
+                    _ => record.delete_variation(&selected),
+                };
+                // A game in progress against the clock or an engine keeps its main line, since
+                // neither of them could follow the live position jumping to another line.
+                if self.is_live_game() && !record.same_mainline(self.game.record()) {
+                    return Command::none();
+                }
+                *self.game.record_mut() = record;
+                self.replay.select(new_path, self.game.record());
+                self.sync_live_game();
+                Command::none()
+            }
+            Message::CommentChanged(comment) => {
+                if self.is_rated_peer_game() {
+                    return Command::none();
+                }
+                let selected = self.selected_path();
+                if let Some(recorded_move) = self.game.record_mut().node_mut(&selected) {
+                    recorded_move.comment =
+                        Some(Pgn::comment_text(&comment)).filter(|comment| !comment.is_empty());
+                }
+                Command::none()
+            }
+            Message::ToggleNag(nag) => {
+                if self.is_rated_peer_game() {
+                    return Command::none();
+                }
+                let selected = self.selected_path();
+                if let Some(recorded_move) = self.game.record_mut().node_mut(&selected) {
+                    recorded_move.toggle_nag(nag);
+                }
+                Command::none()
+            }
+            Message::ResumeSession => {
+                if let Some(session) = self.saved_session.take() {
+                    if let Err(err) = self.resume_session(session) {
+                        self.session_error = Some(err);
+                    }
+                }
+                Command::none()
+            }
+            Message::DiscardSession => {
+                self.saved_session = None;
+                if let Err(err) = Session::clear() {
+                    self.session_error =
+                        Some(format!("The saved game could not be removed: {}", err));
+                }
+                Command::none()
+            }
+            Message::DismissSessionError => {
+                self.session_error = None;
+                Command::none()
+            }
+            Message::ToggleGameBrowser => {
+                self.browser.toggle();
+                Command::none()
+            }
+            Message::BrowserOpponentChanged(opponent_filter) => {
+                self.browser.set_opponent_filter(opponent_filter);
+                Command::none()
+            }
+            Message::BrowserResultSelected(result_filter) => {
+                self.browser.set_result_filter(result_filter);
+                Command::none()
+            }
+            Message::BrowserOpeningChanged(opening_filter) => {
+                self.browser.set_opening_filter(opening_filter);
+                Command::none()
+            }
+            Message::SearchCurrentPosition => {
+                let board = match self.replay.position() {
+                    Some((replayed_position, _)) => replayed_position,
+                    None => self.game.board(),
+                };
+                let games = self.browser.search_position(board);
+                App::index_games(games)
+            }
+            Message::PositionIndexBuilt(index) => {
+                self.browser.set_index(index);
+                Command::none()
+            }
+            Message::BrowserPatternChanged(pattern) => {
+                self.browser.set_pattern(pattern);
+                Command::none()
+            }
+            Message::SearchPattern => {
+                let games = self.browser.search_pattern();
+                App::index_games(games)
+            }
+            Message::ClearSearch => {
+                self.browser.clear_search();
+                Command::none()
+            }
+            Message::ToggleSettings => {
+                self.settings.toggle();
+                Command::none()
+            }
+            Message::BoardSizeSelected(board_size) => {
+                self.config.board_size = board_size;
+                self.settings.save(&self.config);
+                Command::none()
+            }
+            Message::ThemeSelected(theme) => {
+                self.config.theme = theme;
+                self.settings.save(&self.config);
+                Command::none()
+            }
+            Message::AntialiasingToggled(antialiasing) => {
+                self.config.antialiasing = antialiasing;
+                self.settings.save(&self.config);
+                Command::none()
+            }
+            Message::BoardColorChanged(kind, input) => {
+                self.settings.edit_color(kind, input, &mut self.config);
+                Command::none()
+            }
+            Message::ResetSettings => {
+                self.settings.reset(&mut self.config);
+                Command::none()
+            }
+            Message::DismissStartupError => {
+                self.startup_error = None;
+                Command::none()
+            }
+            Message::OpenStoredGame(index) => {
+                if let Some(pgn) = self.browser.open(index) {
+                    match self.import_pgn(&pgn) {
+                        Ok(()) => {
+                            // Stored games are finished, so their clock must not run again.
+                            self.clock = None;
+                            self.pgn_error = None;
+                        }
+                        Err(err) => self.pgn_error = Some(err),
+                    }
                 }
                 Command::none()
             }
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         let toggle_board_handle = svg::Handle::from_path(format!(
             "{}/resources/images/swap_vertical.svg",
             env!("CARGO_MANIFEST_DIR")
@@ -62,30 +812,828 @@ impl Application for App {
         let toggle_board_image = svg(toggle_board_handle)
             .width(Length::Fill)
             .height(Length::Fill);
-        let mut board = ChessBoard::new(400u16);
-        board.set_game(self.game.clone());
-        board.set_orientation(self.black_at_bottom);
-        board.set_on_new_position(Box::new(|new_fen| Message::UpdateBoardPosition(new_fen)));
+        let mut board = ChessBoard::new(self.config.board_size)
+            .style(self.board_style())
+            .orientation(self.black_at_bottom)
+            .controlled(true);
+        match self.replay.position() {
+            Some((replayed_position, replayed_castling)) => {
+                board = board.game(replayed_position.clone());
+                if !self.is_rated_peer_game() {
+                    board = board.on_move(Message::BoardMove);
+                }
+                if let Some(castling) = replayed_castling {
+                    board = board.chess960_castling(*castling);
+                }
+            }
+            None => {
+                board = board.game(self.game.board().clone());
+                if self.accepts_user_moves() {
+                    board = board
+                        .movable_sides(self.game.movable_sides())
+                        .on_move(Message::BoardMove);
+                }
+                if let Some((src, dest)) = self.hint_arrow {
+                    board = board.arrow(src, dest);
+                }
+                if let Some(castling) = self.game.chess960_castling().copied() {
+                    board = board.chess960_castling(castling);
+                }
+            }
+        }
+        let displayed_move = Some(self.selected_path())
+            .filter(|_| self.setup.is_none())
+            .and_then(|path| self.game.record().node(&path));
+        if let Some(displayed_move) = displayed_move {
+            let glyphs = displayed_move.glyphs();
+            if let Some(square) = displayed_move.destination().filter(|_| !glyphs.is_empty()) {
+                board = board.glyph(square, glyphs);
+            }
+        }
+
+        let mut buttons_row = Row::new().align_items(Alignment::Center).spacing(5).push(
+            button(toggle_board_image)
+                .width(40)
+                .height(40)
+                .on_press(Message::ToggleBoardOrientation),
+        );
+        if self.setup.is_none() {
+            buttons_row =
+                buttons_row.push(button(text("Setup position")).on_press(Message::EnterSetupMode));
+        }
+        buttons_row = buttons_row
+            .push(button(text("Copy FEN")).on_press(Message::CopyFen))
+            .push(button(text("Paste FEN")).on_press(Message::PasteFen))
+            .push(button(text("Copy PGN")).on_press(Message::CopyPgn))
+            .push(button(text("Paste PGN")).on_press(Message::PastePgn))
+            .push(button(text("Games")).on_press(Message::ToggleGameBrowser))
+            .push(button(text("Settings")).on_press(Message::ToggleSettings));
+        if self.setup.is_none() {
+            let hint_label = if self.hint_pending {
+                String::from("Searching...")
+            } else {
+                format!("Hint ({} left)", self.hints_left)
+            };
+            let mut hint_button = button(text(hint_label));
+            if self.can_request_hint() {
+                hint_button = hint_button.on_press(Message::RequestHint);
+            }
+            buttons_row = buttons_row.push(hint_button);
+        }
+
+        let mut new_game_row = Row::new()
+            .align_items(Alignment::Center)
+            .spacing(5)
+            .push(button(text("New game")).on_press(Message::NewGame))
+            .push(
+                text_input(
+                    "0-959",
+                    &self.chess960_number,
+                    Message::Chess960NumberChanged,
+                )
+                .on_submit(Message::NewChess960Game)
+                .width(60),
+            )
+            .push(button(text("Chess960")).on_press(Message::NewChess960Game))
+            .push(button(text("Random Chess960")).on_press(Message::NewRandomChess960Game))
+            .push(pick_list(
+                &TimeControlChoice::ALL[..],
+                Some(TimeControlChoice(self.time_control)),
+                Message::TimeControlSelected,
+            ));
+        if self.opponent.engine_color().is_none() {
+            new_game_row = new_game_row.push(checkbox("Rated", self.rated, Message::RatedToggled));
+        }
+
+        let mut board_row = Row::new().spacing(10);
+        match self.setup {
+            Some(ref setup) => {
+                let board = board
+                    .setup_position(setup.clone())
+                    .on_setup_cell_pressed(Message::SetupCellPressed);
+                board_row = board_row.push(board).push(self.setup_panel(setup));
+            }
+            None => {
+                if self.analysis.is_running() && !self.is_rated_peer_game() {
+                    let mut evaluation_bar = EvaluationBar::new(30, self.config.board_size);
+                    evaluation_bar.set_white_share(self.displayed_evaluation);
+                    evaluation_bar.set_orientation(self.black_at_bottom);
+                    if let Some(score) = self.analysis.score() {
+                        evaluation_bar.set_label(EvaluationBar::label_of(score));
+                    }
+                    board_row = board_row.push(evaluation_bar);
+                }
+                let mut side_column = Column::new()
+                    .spacing(15)
+                    .push(self.opponent.view(self.analysis.engine_path()));
+                side_column = if self.is_rated_peer_game() {
+                    side_column.push(text("Engine analysis is disabled during rated games"))
+                } else {
+                    side_column
+                        .push(self.analysis.view(self.game.board()))
+                        .push(self.book.view(
+                            self.game.board(),
+                            self.game.chess960_castling(),
+                            self.accepts_user_moves(),
+                        ))
+                        .push(self.tablebase.view(self.game.board()))
+                };
+                let game_over = self.is_game_over();
+                if game_over || self.review.is_reviewing() || self.review.has_review() {
+                    side_column = side_column.push(self.review.view(self.game.record(), game_over));
+                }
+                let opening_label = match self.opening {
+                    Some(opening) => opening.to_string(),
+                    None => String::new(),
+                };
+                let mut board_column = Column::new().spacing(5).push(board);
+                if !self.game.record().moves().is_empty() {
+                    board_column = board_column
+                        .push(self.replay.view(self.game.record()))
+                        .push(MoveList::view(
+                            self.game.record(),
+                            &self.selected_path(),
+                            !self.is_rated_peer_game(),
+                        ));
+                }
+                board_row = board_row
+                    .push(board_column.push(text(opening_label).size(18)))
+                    .push(side_column);
+            }
+        }
+
+        let mut main_column = Column::new()
+            .align_items(Alignment::Center)
+            .spacing(5)
+            .push(new_game_row)
+            .push(buttons_row);
+        if let Some(ref fen_error) = self.fen_error {
+            main_column = main_column.push(App::error_dialog(
+                "Invalid FEN",
+                fen_error,
+                Message::DismissFenError,
+            ));
+        }
+        if self.saved_session.is_some() {
+            main_column = main_column.push(
+                container(
+                    Column::new()
+                        .spacing(5)
+                        .align_items(Alignment::Center)
+                        .push(text("Unfinished game").size(20))
+                        .push(text(
+                            "The game in progress when the app was closed was saved",
+                        ))
+                        .push(
+                            Row::new()
+                                .spacing(5)
+                                .push(button(text("Resume")).on_press(Message::ResumeSession))
+                                .push(button(text("Discard")).on_press(Message::DiscardSession)),
+                        ),
+                )
+                .padding(10)
+                .style(theme::Container::Box),
+            );
+        }
+        if let Some(ref session_error) = self.session_error {
+            main_column = main_column.push(App::error_dialog(
+                "Saved game",
+                session_error,
+                Message::DismissSessionError,
+            ));
+        }
+        if let Some(ref pgn_error) = self.pgn_error {
+            main_column = main_column.push(App::error_dialog(
+                "Invalid PGN",
+                pgn_error,
+                Message::DismissPgnError,
+            ));
+        }
+        if let Some(ref clock) = self.clock {
+            main_column = main_column.push(self.clock_row(clock));
+        }
+        if let Some(ref startup_error) = self.startup_error {
+            main_column = main_column.push(App::error_dialog(
+                "Startup",
+                startup_error,
+                Message::DismissStartupError,
+            ));
+        }
+        main_column = if self.settings.is_visible() {
+            main_column.push(self.settings.view(&self.config))
+        } else if self.browser.is_visible() {
+            main_column.push(self.browser.view())
+        } else {
+            main_column.push(board_row)
+        };
+
+        container(main_column)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        let clock_ticks = match self.clock {
+            Some(ref clock) if clock.is_running() => {
+                iced::time::every(CLOCK_TICK).map(|_| Message::ClockTick)
+            }
+            _ => Subscription::none(),
+        };
+        let evaluation_animation = if self.analysis.is_running()
+            && (self.target_evaluation() - self.displayed_evaluation).abs() > f32::EPSILON
+        {
+            iced::time::every(EVALUATION_ANIMATION_TICK).map(|_| Message::EvaluationAnimationTick)
+        } else {
+            Subscription::none()
+        };
+        let replay_keys = if self.setup.is_none() {
+            iced::subscription::events_with(|event, status| match (event, status) {
+                (
+                    Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }),
+                    event::Status::Ignored,
+                ) => match key_code {
+                    KeyCode::Left => Some(Message::ReplayPrevious),
+                    KeyCode::Right => Some(Message::ReplayNext),
+                    KeyCode::Home | KeyCode::Up => Some(Message::ReplayFirst),
+                    KeyCode::End | KeyCode::Down => Some(Message::ReplayLast),
+                    _ => None,
+                },
+                _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+        Subscription::batch([
+            self.analysis.subscription().map(Message::Analysis),
+            self.opponent
+                .subscription(self.analysis.engine_path())
+                .map(Message::Opponent),
+            clock_ticks,
+            evaluation_animation,
+            self.replay.subscription(),
+            replay_keys,
+        ])
+    }
+
+    fn theme(&self) -> Theme {
+        match self.config.theme {
+            ThemeChoice::Light => Theme::Light,
+            ThemeChoice::Dark => Theme::Dark,
+        }
+    }
+}
+
+impl App {
+    fn start_game(&mut self, game: Board, chess960_castling: Option<Chess960Castling>) {
+        self.start_recorded_game(GameController::new(game, chess960_castling));
+    }
+
+    fn start_recorded_game(&mut self, game: GameController) {
+        self.opponent.stop();
+        self.game = game;
+        self.game.set_movable_sides(self.movable_sides());
+        self.replay.reset();
+        self.hints_left = hint::HINTS_PER_GAME;
+        self.hint_arrow = None;
+        self.review.clear();
+        self.update_opening();
+        self.tablebase.probe(self.game.board());
+        self.clock = self.time_control.map(|time_control| {
+            let mut clock = GameClock::new(time_control, self.game.turn());
+            clock.start();
+            clock
+        });
+        self.request_analysis();
+        self.request_engine_move();
+        self.autosave();
+    }
+
+    fn after_move(&mut self) -> Command<Message> {
+        if let Some(ref mut clock) = self.clock {
+            clock.switch_turn();
+        }
+        self.hint_arrow = None;
+        self.advance_opening();
+        self.tablebase.probe(self.game.board());
+        self.request_analysis();
+        self.autosave();
+        if self.is_game_over() {
+            if let Some(ref mut clock) = self.clock {
+                clock.stop();
+            }
+            self.store_finished_game();
+            return self.review_game();
+        }
+        self.request_engine_move();
+        Command::none()
+    }
+
+    fn update_opening(&mut self) {
+        self.opening = self.game.record().opening();
+    }
+
+    // A single new move can only rename the opening through the position it reaches, so there is
+    // no need to replay the whole game as `update_opening` does.
+    fn advance_opening(&mut self) {
+        if self.game.record().is_chess960() {
+            return;
+        }
+        if let Some(opening) = EcoTable::get().lookup(self.game.board()) {
+            self.opening = Some(opening);
+        }
+    }
+
+    fn selected_path(&self) -> MovePath {
+        self.replay
+            .cursor()
+            .cloned()
+            .unwrap_or_else(|| self.game.record().mainline_end())
+    }
+
+    fn add_variation_move(&mut self, cursor: &MovePath, move_event: MoveEvent) {
+        if self.is_rated_peer_game() {
+            return;
+        }
+        // The board may report a move made just before the cursor moved elsewhere.
+        let legal = self
+            .game
+            .record()
+            .position_at(cursor)
+            .and_then(|(position, castling)| {
+                Moves::play_uci_move(&position, castling.as_ref(), &move_event.uci)
+            })
+            .is_some();
+        if !legal {
+            return;
+        }
+        let recorded_move = RecordedMove::new(move_event.san, move_event.uci);
+        if let Some(new_path) = self.game.record_mut().add_move(cursor, recorded_move) {
+            self.replay.select(new_path, self.game.record());
+            self.sync_live_game();
+        }
+    }
+
+    fn sync_live_game(&mut self) {
+        let previous_fen = self.current_fen();
+        self.game.sync_to_mainline();
+        if self.current_fen() == previous_fen {
+            return;
+        }
+        self.opponent.stop();
+        self.hint_arrow = None;
+        self.review.clear();
+        self.update_opening();
+        self.tablebase.probe(self.game.board());
+        self.request_analysis();
+        self.request_engine_move();
+        self.autosave();
+    }
+
+    fn apply_launch_options(&mut self, launch: LaunchOptions) {
+        let mut errors: Vec<String> = self.startup_error.take().into_iter().collect();
+        if let Some(play_black) = launch.play_black {
+            self.black_at_bottom = play_black;
+        }
+        if launch.time_control.is_some() {
+            self.time_control = launch.time_control;
+        }
+        if let Some(fen) = launch.fen {
+            if let Err(err) = self.start_game_from_fen(fen.trim()) {
+                errors.push(format!("Invalid FEN: {}", err));
+            }
+        } else if let Some(pgn_path) = launch.pgn_path {
+            let imported = fs::read_to_string(&pgn_path)
+                .map_err(|err| err.to_string())
+                .and_then(|pgn| self.import_pgn(&pgn));
+            if let Err(err) = imported {
+                errors.push(format!("Could not open {}: {}", pgn_path.display(), err));
+            }
+        } else if launch.time_control.is_some() {
+            self.start_game(Board::start_pos(), None);
+        }
+        if !errors.is_empty() {
+            self.startup_error = Some(errors.join("\n"));
+        }
+    }
+
+    fn start_game_from_fen(&mut self, fen: &str) -> Result<(), SetupError> {
+        let (new_logic, new_castling) = Chess960::any_position_from_fen(fen)?;
+        self.start_game(new_logic, new_castling);
+        Ok(())
+    }
+
+    fn import_pgn(&mut self, pgn: &str) -> Result<(), String> {
+        let game = GameController::from_record(GameRecord::from_pgn(pgn)?)?;
+        self.start_recorded_game(game);
+        self.replay.first(self.game.record());
+        Ok(())
+    }
+
+    fn index_games(games: Option<Vec<StoredGame>>) -> Command<Message> {
+        match games {
+            Some(games) => Command::perform(
+                PositionIndex::build_in_background(games),
+                Message::PositionIndexBuilt,
+            ),
+            None => Command::none(),
+        }
+    }
+
+    fn store_finished_game(&mut self) {
+        // The opponent sits across the board from the player at the bottom.
+        let (opponent_color, opponent) = match self.opponent.engine_color() {
+            Some(engine_color) => (
+                engine_color,
+                self.opponent.engine_display_name().to_string(),
+            ),
+            None => {
+                let peer_color = if self.black_at_bottom {
+                    Player::White
+                } else {
+                    Player::Black
+                };
+                let peer_name = Some(self.opponent.peer_name().trim())
+                    .filter(|name| !name.is_empty())
+                    .or_else(|| self.game.record().player_name(peer_color))
+                    .unwrap_or("Peer")
+                    .to_string();
+                (peer_color, peer_name)
+            }
+        };
+        self.game
+            .record_mut()
+            .set_player_name(opponent_color, &opponent);
+        self.browser.add(StoredGame {
+            opponent,
+            date: self.game.record().date().to_string(),
+            result: self.result().to_string(),
+            time_control: self
+                .clock
+                .as_ref()
+                .map(|clock| clock.time_control().to_string()),
+            opening: self.opening.map(|opening| opening.to_string()),
+            pgn: self.game.record().to_pgn(self.result()),
+        });
+    }
+
+    fn autosave(&mut self) {
+        // A session that was not resumed is kept until another game gets under way.
+        if self.saved_session.is_some() && self.game.record().moves().is_empty() {
+            return;
+        }
+        self.saved_session = None;
+        let saved = if self.game.record().moves().is_empty() || self.is_game_over() {
+            Session::clear()
+        } else {
+            Session {
+                pgn: self.game.record().to_pgn(self.result()),
+                chess960_number: self.chess960_number.clone(),
+                black_at_bottom: self.black_at_bottom,
+                clock: self.clock.as_ref().map(|clock| SavedClock {
+                    time_control: clock.time_control(),
+                    white_remaining: clock.remaining(Player::White),
+                    black_remaining: clock.remaining(Player::Black),
+                }),
+                opponent: self.opponent.settings(),
+                engine_path: self.analysis.engine_path().to_string(),
+                rated: self.rated,
+                hints_left: self.hints_left,
+            }
+            .save()
+        };
+        if let Err(err) = saved {
+            self.session_error = Some(format!("The game could not be saved: {}", err));
+        }
+    }
+
+    fn resume_session(&mut self, session: Session) -> Result<(), String> {
+        let game = GameController::from_record(GameRecord::from_pgn(&session.pgn)?)
+            .map_err(|_| String::from("The saved game could not be replayed"))?;
+        self.chess960_number = session.chess960_number;
+        self.black_at_bottom = session.black_at_bottom;
+        self.rated = session.rated;
+        self.analysis.set_engine_path(session.engine_path);
+        self.opponent.restore(session.opponent);
+        self.time_control = session.clock.as_ref().map(|clock| clock.time_control);
+        self.start_recorded_game(game);
+        self.hints_left = session.hints_left;
+        if let Some(saved_clock) = session.clock {
+            let mut clock = GameClock::resume(
+                saved_clock.time_control,
+                saved_clock.white_remaining,
+                saved_clock.black_remaining,
+                self.game.turn(),
+            );
+            clock.start();
+            self.clock = Some(clock);
+        }
+        self.autosave();
+        Ok(())
+    }
+
+    fn review_game(&mut self) -> Command<Message> {
+        if self.game.record().moves().is_empty() || self.review.is_reviewing() {
+            return Command::none();
+        }
+        self.review.start();
+        let engine_path = Some(self.analysis.engine_path())
+            .filter(|engine_path| !engine_path.is_empty())
+            .map(String::from);
+        let game_key = self.game_key();
+        Command::perform(
+            GameReview::run(self.game.record().clone(), engine_path),
+            move |result| Message::ReviewFinished(game_key.clone(), result),
+        )
+    }
+
+    fn game_key(&self) -> String {
+        format!(
+            "{} {}",
+            self.game.record().start_fen(),
+            self.record_uci_moves().join(" ")
+        )
+    }
+
+    fn is_game_over(&self) -> bool {
+        let flagged = self
+            .clock
+            .as_ref()
+            .map(|clock| clock.flagged().is_some())
+            .unwrap_or(false);
+        flagged || self.game.status() != GameStatus::Ongoing
+    }
+
+    fn can_request_hint(&self) -> bool {
+        self.hints_left > 0
+            && !self.hint_pending
+            && self.hint_arrow.is_none()
+            && !self.is_rated_peer_game()
+            && self.accepts_user_moves()
+    }
+
+    fn is_live_game(&self) -> bool {
+        !self.is_game_over() && (self.clock.is_some() || self.opponent.engine_color().is_some())
+    }
+
+    fn is_rated_peer_game(&self) -> bool {
+        self.rated && self.opponent.engine_color().is_none()
+    }
+
+    fn target_evaluation(&self) -> f32 {
+        self.analysis
+            .score()
+            .map(EvaluationBar::white_share_of)
+            .unwrap_or(self.displayed_evaluation)
+    }
+
+    /// Follows the theme unless the board colors were customized in the settings.
+    fn board_style(&self) -> BoardStyle {
+        let colors = &self.config.colors;
+        if *colors == BoardColors::default() {
+            return BoardStyle::Default;
+        }
+        let color = |RgbColor(r, g, b): RgbColor| Color::from_rgb8(r, g, b);
+        let defaults = self.theme().appearance(&BoardStyle::Default);
+        Appearance {
+            background: color(colors.background),
+            light_squares: color(colors.light_squares),
+            dark_squares: color(colors.dark_squares),
+            coordinates: color(colors.coordinates),
+            arrow: Color {
+                a: defaults.arrow.a,
+                ..color(colors.arrow)
+            },
+            ..defaults
+        }
+        .into()
+    }
+
+    fn movable_sides(&self) -> MovableSides {
+        match self.opponent.engine_color() {
+            Some(engine_color) => MovableSides::Only(!engine_color),
+            None => MovableSides::Both,
+        }
+    }
+
+    fn accepts_user_moves(&self) -> bool {
+        !self.is_game_over() && self.game.can_move()
+    }
+
+    fn result(&self) -> &'static str {
+        match self.clock.as_ref().and_then(|clock| clock.flagged()) {
+            Some(Player::White) => "0-1",
+            Some(Player::Black) => "1-0",
+            None => self.game.status().result(),
+        }
+    }
+
+    fn request_engine_move(&mut self) {
+        if self.is_game_over()
+            || !self.opponent.is_ready()
+            || !self.opponent.is_engine_turn(self.game.turn())
+        {
+            return;
+        }
+        let clock = self.clock.as_ref().map(|clock| ClockTimes {
+            white: clock.remaining(Player::White),
+            black: clock.remaining(Player::Black),
+            increment: clock.time_control().increment,
+        });
+        self.opponent.request_move(
+            self.game.record().start_fen().to_string(),
+            self.record_uci_moves(),
+            self.current_fen(),
+            self.game.chess960_castling().is_some(),
+            clock,
+        );
+    }
+
+    fn record_uci_moves(&self) -> Vec<String> {
+        self.game
+            .record()
+            .moves()
+            .iter()
+            .map(|recorded_move| recorded_move.uci.clone())
+            .collect()
+    }
+
+    fn request_analysis(&mut self) {
+        if self.game.status() != GameStatus::Ongoing {
+            self.analysis.analyze(AnalysisCommand::Stop);
+            return;
+        }
+        self.analysis.analyze(AnalysisCommand::Analyze {
+            start_fen: self.game.record().start_fen().to_string(),
+            moves: self.record_uci_moves(),
+            position_fen: self.current_fen(),
+            chess960: self.game.chess960_castling().is_some(),
+        });
+    }
+
+    fn current_fen(&self) -> String {
+        self.game.fen()
+    }
+
+    fn clock_row(&self, clock: &GameClock) -> Element<'_, Message> {
+        let clock_text = |player: Player| {
+            let label = match player {
+                Player::White => "White",
+                Player::Black => "Black",
+            };
+            let remaining = format!("{} {}", label, GameClock::format(clock.remaining(player)));
+            let color = if clock.flagged() == Some(player) {
+                Color::from_rgb8(0xC6, 0x28, 0x28)
+            } else if clock.is_running() && self.game.turn() == player {
+                Color::from_rgb8(0x2E, 0x7D, 0x32)
+            } else {
+                Color::BLACK
+            };
+            text(remaining).size(24).style(color)
+        };
+        let (left, right) = if self.black_at_bottom {
+            (Player::Black, Player::White)
+        } else {
+            (Player::White, Player::Black)
+        };
+        Row::new()
+            .spacing(40)
+            .push(clock_text(left))
+            .push(clock_text(right))
+            .into()
+    }
+
+    fn error_dialog<'a>(title: &str, error: &str, on_close: Message) -> Element<'a, Message> {
         container(
             Column::new()
-                .align_items(Alignment::Center)
                 .spacing(5)
-                .push(
-                    button(toggle_board_image)
-                        .width(40)
-                        .height(40)
-                        .on_press(Message::ToggleBoardOrientation),
-                )
-                .push(board),
+                .align_items(Alignment::Center)
+                .push(text(title).size(20))
+                .push(text(error).style(Color::from_rgb8(0xC6, 0x28, 0x28)))
+                .push(button(text("Close")).on_press(on_close)),
         )
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .center_x()
-        .center_y()
+        .padding(10)
+        .style(theme::Container::Box)
         .into()
     }
 
-    fn theme(&self) -> Theme {
-        Theme::Light
+    fn setup_panel(&self, setup: &PositionSetup) -> Element<'_, Message> {
+        let pieces_images = PiecesVectors::new();
+        let palette_row = |pieces: [Piece; 6]| {
+            pieces
+                .into_iter()
+                .fold(Row::new().spacing(2), |row, piece| {
+                    let piece_button = match pieces_images.piece_handle(piece) {
+                        Some(handle) => button(svg(handle).width(30).height(30)),
+                        None => button(text("")),
+                    };
+                    let style = if piece == self.setup_piece {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Secondary
+                    };
+                    row.push(
+                        piece_button
+                            .style(style)
+                            .on_press(Message::SelectSetupPiece(piece)),
+                    )
+                })
+        };
+        let eraser_style = if self.setup_piece == Piece::None {
+            theme::Button::Primary
+        } else {
+            theme::Button::Secondary
+        };
+
+        let white_to_move = setup.turn() == Player::White;
+        let turn_row = Row::new()
+            .spacing(10)
+            .push(radio(
+                "White to move",
+                true,
+                Some(white_to_move),
+                Message::SetupWhiteToMove,
+            ))
+            .push(radio(
+                "Black to move",
+                false,
+                Some(white_to_move),
+                Message::SetupWhiteToMove,
+            ));
+
+        let castling_column =
+            CastlingRight::ALL
+                .into_iter()
+                .fold(Column::new().spacing(2), |column, right| {
+                    column.push(checkbox(
+                        right.to_string(),
+                        setup.can_castle(right),
+                        move |enabled| Message::SetupCastlingToggled(right, enabled),
+                    ))
+                });
+
+        let en_passant_row = Row::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(text("En passant file"))
+            .push(pick_list(
+                &EnPassantChoice::ALL[..],
+                Some(EnPassantChoice(setup.en_passant_file())),
+                Message::SetupEnPassantSelected,
+            ));
+
+        let validation = setup.validate();
+        let status = match validation {
+            Ok(_) => text("Position is valid").style(Color::from_rgb8(0x2E, 0x7D, 0x32)),
+            Err(ref err) => text(err.to_string()).style(Color::from_rgb8(0xC6, 0x28, 0x28)),
+        };
+        let mut start_button = button(text("Start game from here"));
+        if validation.is_ok() {
+            start_button = start_button.on_press(Message::StartGameFromSetup);
+        }
+
+        Column::new()
+            .spacing(8)
+            .width(300)
+            .push(palette_row([
+                Piece::WhiteKing,
+                Piece::WhiteQueen,
+                Piece::WhiteRook,
+                Piece::WhiteBishop,
+                Piece::WhiteKnight,
+                Piece::WhitePawn,
+            ]))
+            .push(palette_row([
+                Piece::BlackKing,
+                Piece::BlackQueen,
+                Piece::BlackRook,
+                Piece::BlackBishop,
+                Piece::BlackKnight,
+                Piece::BlackPawn,
+            ]))
+            .push(
+                button(text("Eraser"))
+                    .style(eraser_style)
+                    .on_press(Message::SelectSetupPiece(Piece::None)),
+            )
+            .push(turn_row)
+            .push(castling_column)
+            .push(en_passant_row)
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .push(button(text("Clear")).on_press(Message::ClearSetupBoard))
+                    .push(button(text("Initial position")).on_press(Message::ResetSetupBoard)),
+            )
+            .push(status)
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .push(start_button)
+                    .push(button(text("Cancel")).on_press(Message::CancelSetup)),
+            )
+            .into()
     }
 }
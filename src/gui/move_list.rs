@@ -0,0 +1,142 @@
+use iced::theme;
+use iced::widget::{button, scrollable, text, text_input, Column, Row};
+use iced::{Alignment, Element};
+
+use super::Message;
+use crate::game_record::{GameRecord, MovePath, RecordedMove};
+use crate::pgn::Pgn;
+
+const MOVES_PER_ROW: usize = 6;
+const VARIATION_INDENT: u16 = 20;
+const MOVE_NAGS: [u8; 6] = [1, 2, 3, 4, 5, 6];
+const POSITION_NAGS: [u8; 8] = [10, 13, 14, 15, 16, 17, 18, 19];
+
+pub struct MoveList {}
+
+impl MoveList {
+    pub fn view<'a>(
+        record: &'a GameRecord,
+        selected: &MovePath,
+        editable: bool,
+    ) -> Element<'a, Message> {
+        let lines = MoveList::line_view(record, record.moves(), &MovePath::start(), 0, selected);
+
+        let mut operations = Row::new().spacing(5).align_items(Alignment::Center);
+        if editable {
+            let in_variation = selected.is_in_variation();
+            let mut promote_button = button(text("Promote").size(16));
+            let mut delete_button = button(text("Delete variation").size(16));
+            if in_variation {
+                promote_button = promote_button.on_press(Message::PromoteVariation);
+                delete_button = delete_button.on_press(Message::DeleteVariation);
+            }
+            let mut demote_button = button(text("Demote").size(16));
+            if !selected.is_start() {
+                demote_button = demote_button.on_press(Message::DemoteVariation);
+            }
+            operations = operations
+                .push(promote_button)
+                .push(demote_button)
+                .push(delete_button);
+        }
+
+        let mut column = Column::new()
+            .spacing(5)
+            .width(400)
+            .push(scrollable(lines).height(150))
+            .push(operations);
+        if let Some(recorded_move) = record.node(selected).filter(|_| editable) {
+            column = column.push(MoveList::annotation_view(recorded_move));
+        }
+        column.into()
+    }
+
+    fn annotation_view(recorded_move: &RecordedMove) -> Column<'_, Message> {
+        let glyphs_row = |nags: &[u8]| {
+            nags.iter().fold(Row::new().spacing(2), |row, nag| {
+                let style = if recorded_move.nags.contains(nag) {
+                    theme::Button::Primary
+                } else {
+                    theme::Button::Secondary
+                };
+                row.push(
+                    button(text(Pgn::nag_glyph(*nag).unwrap_or_default()).size(16))
+                        .padding([2, 6])
+                        .style(style)
+                        .on_press(Message::ToggleNag(*nag)),
+                )
+            })
+        };
+        Column::new()
+            .spacing(5)
+            .push(
+                text_input(
+                    "Comment",
+                    recorded_move.comment.as_deref().unwrap_or_default(),
+                    Message::CommentChanged,
+                )
+                .size(16),
+            )
+            .push(glyphs_row(&MOVE_NAGS))
+            .push(glyphs_row(&POSITION_NAGS))
+    }
+
+    fn line_view<'a>(
+        record: &'a GameRecord,
+        line: &'a [RecordedMove],
+        line_start: &MovePath,
+        first_ply: usize,
+        selected: &MovePath,
+    ) -> Column<'a, Message> {
+        let mut column = Column::new().spacing(2);
+        let mut row = Row::new().spacing(2).align_items(Alignment::Center);
+        let mut row_length = 0;
+
+        for (index, recorded_move) in line.iter().enumerate() {
+            let path = line_start.child(index);
+            let move_number = record.move_number_prefix(first_ply + index);
+            let interrupted = index == 0 || !line[index - 1].variations.is_empty();
+            let mut label = if !move_number.ends_with("...") || interrupted {
+                format!("{} {}", move_number, recorded_move.san)
+            } else {
+                recorded_move.san.clone()
+            };
+            label.push_str(&recorded_move.glyphs());
+            let style = if path == *selected {
+                theme::Button::Primary
+            } else {
+                theme::Button::Text
+            };
+            row = row.push(
+                button(text(label).size(16))
+                    .padding(2)
+                    .style(style)
+                    .on_press(Message::SelectMove(path)),
+            );
+            row_length += 1;
+
+            if row_length == MOVES_PER_ROW || !recorded_move.variations.is_empty() {
+                column = column.push(row);
+                row = Row::new().spacing(2).align_items(Alignment::Center);
+                row_length = 0;
+            }
+            for (variation_index, variation) in recorded_move.variations.iter().enumerate() {
+                let variation_start = line_start.child(index).child(variation_index);
+                column = column.push(
+                    MoveList::line_view(
+                        record,
+                        variation,
+                        &variation_start,
+                        first_ply + index,
+                        selected,
+                    )
+                    .padding([0, 0, 0, VARIATION_INDENT]),
+                );
+            }
+        }
+        if row_length > 0 {
+            column = column.push(row);
+        }
+        column
+    }
+}
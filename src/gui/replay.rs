@@ -0,0 +1,166 @@
+use std::fmt;
+use std::time::Duration;
+
+use iced::widget::{button, pick_list, text, Row};
+use iced::{Alignment, Element, Subscription};
+use iced_chessboard::chess960::Chess960Castling;
+use pleco::Board;
+
+use super::Message;
+use crate::game_record::{GameRecord, MovePath};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplaySpeed {
+    Slow,
+    Normal,
+    Fast,
+}
+
+impl ReplaySpeed {
+    pub const ALL: [ReplaySpeed; 3] = [ReplaySpeed::Slow, ReplaySpeed::Normal, ReplaySpeed::Fast];
+
+    fn interval(self) -> Duration {
+        match self {
+            ReplaySpeed::Slow => Duration::from_secs(3),
+            ReplaySpeed::Normal => Duration::from_millis(1500),
+            ReplaySpeed::Fast => Duration::from_millis(500),
+        }
+    }
+}
+
+impl fmt::Display for ReplaySpeed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplaySpeed::Slow => write!(f, "Slow"),
+            ReplaySpeed::Normal => write!(f, "Normal"),
+            ReplaySpeed::Fast => write!(f, "Fast"),
+        }
+    }
+}
+
+pub struct Replay {
+    cursor: Option<MovePath>,
+    /// The position at the cursor, replayed once when the cursor moves instead of on every view.
+    position: Option<(Board, Option<Chess960Castling>)>,
+    autoplay: bool,
+    speed: ReplaySpeed,
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self {
+            cursor: None,
+            position: None,
+            autoplay: false,
+            speed: ReplaySpeed::Normal,
+        }
+    }
+
+    /// The displayed move, or None when the live position is shown.
+    pub fn cursor(&self) -> Option<&MovePath> {
+        self.cursor.as_ref()
+    }
+
+    /// The displayed position, or None when the live position is shown.
+    pub fn position(&self) -> Option<&(Board, Option<Chess960Castling>)> {
+        self.position.as_ref()
+    }
+
+    pub fn reset(&mut self) {
+        self.cursor = None;
+        self.position = None;
+        self.autoplay = false;
+    }
+
+    pub fn select(&mut self, path: MovePath, record: &GameRecord) {
+        let cursor = Some(path).filter(|path| *path != record.mainline_end());
+        if cursor.is_none() {
+            self.autoplay = false;
+        }
+        self.move_cursor(cursor, record);
+    }
+
+    pub fn first(&mut self, record: &GameRecord) {
+        if !record.moves().is_empty() {
+            self.move_cursor(Some(MovePath::start()), record);
+        }
+    }
+
+    pub fn previous(&mut self, record: &GameRecord) {
+        let current = self.cursor.clone().unwrap_or_else(|| record.mainline_end());
+        if !current.is_start() {
+            self.move_cursor(Some(record.previous_path(&current)), record);
+        }
+    }
+
+    pub fn next(&mut self, record: &GameRecord) {
+        let next = self
+            .cursor
+            .as_ref()
+            .and_then(|current| record.next_path(current));
+        match next {
+            Some(next) => self.select(next, record),
+            None => self.autoplay = false,
+        }
+    }
+
+    pub fn last(&mut self) {
+        self.reset();
+    }
+
+    pub fn toggle_autoplay(&mut self, record: &GameRecord) {
+        self.autoplay = !self.autoplay && !record.moves().is_empty();
+        if self.autoplay && self.cursor.is_none() {
+            self.move_cursor(Some(MovePath::start()), record);
+        }
+    }
+
+    pub fn set_speed(&mut self, speed: ReplaySpeed) {
+        self.speed = speed;
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        if self.autoplay {
+            iced::time::every(self.speed.interval()).map(|_| Message::AutoplayTick)
+        } else {
+            Subscription::none()
+        }
+    }
+
+    fn move_cursor(&mut self, cursor: Option<MovePath>, record: &GameRecord) {
+        self.position = cursor
+            .as_ref()
+            .and_then(|cursor| record.position_at(cursor));
+        self.cursor = cursor;
+    }
+
+    pub fn view(&self, record: &GameRecord) -> Element<'_, Message> {
+        let current = self.cursor.clone().unwrap_or_else(|| record.mainline_end());
+        let position_label = match record.node(&current) {
+            Some(recorded_move) => format!(
+                "{} {}",
+                record.move_number_prefix(current.ply()),
+                recorded_move.san
+            ),
+            None => String::from("Start"),
+        };
+        let live_label = if self.cursor.is_some() { "" } else { " (live)" };
+        let autoplay_label = if self.autoplay { "Pause" } else { "Play" };
+
+        Row::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(button(text("<<")).on_press(Message::ReplayFirst))
+            .push(button(text("<")).on_press(Message::ReplayPrevious))
+            .push(button(text(">")).on_press(Message::ReplayNext))
+            .push(button(text(">>")).on_press(Message::ReplayLast))
+            .push(button(text(autoplay_label)).on_press(Message::ToggleAutoplay))
+            .push(pick_list(
+                &ReplaySpeed::ALL[..],
+                Some(self.speed),
+                Message::ReplaySpeedSelected,
+            ))
+            .push(text(format!("{}{}", position_label, live_label)).width(150))
+            .into()
+    }
+}
@@ -0,0 +1,110 @@
+use iced::widget::{button, scrollable, text, Column};
+use iced::{Color, Element};
+use pleco::Player;
+
+use super::Message;
+use crate::game_record::GameRecord;
+use crate::post_game::GameReview;
+
+pub struct ReviewPanel {
+    reviewing: bool,
+    review: Option<GameReview>,
+    error: Option<String>,
+}
+
+impl ReviewPanel {
+    pub fn new() -> Self {
+        Self {
+            reviewing: false,
+            review: None,
+            error: None,
+        }
+    }
+
+    pub fn is_reviewing(&self) -> bool {
+        self.reviewing
+    }
+
+    pub fn has_review(&self) -> bool {
+        self.review.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.reviewing = false;
+        self.review = None;
+        self.error = None;
+    }
+
+    pub fn start(&mut self) {
+        self.clear();
+        self.reviewing = true;
+    }
+
+    pub fn finish(&mut self, result: Result<GameReview, String>, record: &mut GameRecord) {
+        self.reviewing = false;
+        match result {
+            Ok(review) => {
+                review.annotate(record);
+                self.review = Some(review);
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    pub fn view(&self, record: &GameRecord, game_over: bool) -> Element<'_, Message> {
+        let mut column = Column::new().spacing(5).push(text("Game review").size(20));
+
+        if self.reviewing {
+            return column.push(text("Reviewing the game...")).into();
+        }
+        if let Some(ref error) = self.error {
+            column = column.push(text(error).style(Color::from_rgb8(0xC6, 0x28, 0x28)));
+        }
+
+        match self.review {
+            Some(ref review) => {
+                for (label, player) in [("White", Player::White), ("Black", Player::Black)] {
+                    let summary = review.summary(player);
+                    column = column.push(text(format!(
+                        "{}: {:.1}% accuracy, {:.0} average centipawn loss",
+                        label, summary.accuracy, summary.average_centipawn_loss
+                    )));
+                    column = column.push(
+                        text(format!(
+                            "{} inaccuracies, {} mistakes, {} blunders",
+                            summary.inaccuracies, summary.mistakes, summary.blunders
+                        ))
+                        .size(16),
+                    );
+                }
+
+                let annotated_moves = review
+                    .moves()
+                    .iter()
+                    .zip(record.moves().iter())
+                    .enumerate()
+                    .filter_map(|(index, (move_review, recorded_move))| {
+                        let judgement = move_review.judgement?;
+                        Some(format!(
+                            "{} {}{} ({} cp) {}",
+                            record.move_number_prefix(index),
+                            recorded_move.san,
+                            judgement.glyph(),
+                            -move_review.centipawn_loss,
+                            move_review.comment().unwrap_or_default()
+                        ))
+                    })
+                    .fold(Column::new().spacing(2), |moves_column, line| {
+                        moves_column.push(text(line).size(16))
+                    });
+                column = column.push(scrollable(annotated_moves).height(150));
+            }
+            None if game_over && !record.moves().is_empty() => {
+                column = column.push(button(text("Review game")).on_press(Message::ReviewGame));
+            }
+            None => {}
+        }
+
+        column.into()
+    }
+}
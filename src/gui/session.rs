@@ -0,0 +1,79 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use super::engine_opponent::OpponentSettings;
+use crate::game_clock::TimeControl;
+
+const SESSION_FILE_NAME: &str = "session.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedClock {
+    pub time_control: TimeControl,
+    pub white_remaining: Duration,
+    pub black_remaining: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub pgn: String,
+    pub chess960_number: String,
+    pub black_at_bottom: bool,
+    pub clock: Option<SavedClock>,
+    pub opponent: OpponentSettings,
+    pub engine_path: String,
+    pub rated: bool,
+    pub hints_left: u8,
+}
+
+impl Session {
+    pub fn data_dir() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "peer-chess").map(|dirs| dirs.data_dir().to_path_buf())
+    }
+
+    pub fn load() -> io::Result<Option<Self>> {
+        let path = match Session::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(None),
+        };
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Session::path().ok_or_else(Session::no_data_dir)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        // Write to a temporary file first so a crash never leaves a truncated session behind.
+        let temporary_path = path.with_extension("json.tmp");
+        fs::write(&temporary_path, content)?;
+        fs::rename(temporary_path, path)
+    }
+
+    pub fn clear() -> io::Result<()> {
+        match Session::path() {
+            Some(path) if path.exists() => fs::remove_file(path),
+            _ => Ok(()),
+        }
+    }
+
+    fn path() -> Option<PathBuf> {
+        Session::data_dir().map(|data_dir| data_dir.join(SESSION_FILE_NAME))
+    }
+
+    fn no_data_dir() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "no data directory is available for this user",
+        )
+    }
+}
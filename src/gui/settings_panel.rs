@@ -0,0 +1,145 @@
+use std::io;
+use std::path::PathBuf;
+
+use iced::widget::{button, checkbox, pick_list, svg, text, text_input, Column, Row};
+use iced::{Alignment, Color, Element};
+
+use super::Message;
+use crate::config::{BoardColorKind, Config, RgbColor, ThemeChoice};
+
+pub struct SettingsPanel {
+    path: Option<PathBuf>,
+    visible: bool,
+    color_inputs: Vec<String>,
+    error: Option<String>,
+}
+
+impl SettingsPanel {
+    /// Settings are saved to the given file, or to the user configuration directory.
+    pub fn new(config: &Config, path: Option<PathBuf>) -> Self {
+        Self {
+            path: path.or_else(Config::path),
+            visible: false,
+            color_inputs: SettingsPanel::color_inputs_of(config),
+            error: None,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Keeps the text as typed and applies the color once it is a valid "#RRGGBB" value.
+    pub fn edit_color(&mut self, kind: BoardColorKind, input: String, config: &mut Config) {
+        if let Ok(color) = input.parse::<RgbColor>() {
+            config.colors.set(kind, color);
+            self.save(config);
+        }
+        if let Some(position) = BoardColorKind::ALL.iter().position(|other| *other == kind) {
+            self.color_inputs[position] = input;
+        }
+    }
+
+    pub fn reset(&mut self, config: &mut Config) {
+        *config = Config::default();
+        self.color_inputs = SettingsPanel::color_inputs_of(config);
+        self.save(config);
+    }
+
+    pub fn save(&mut self, config: &Config) {
+        let saved = match self.path {
+            Some(ref path) => config.save(path),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no configuration directory is available for this user",
+            )),
+        };
+        self.error = saved
+            .err()
+            .map(|err| format!("Could not save the settings: {}", err));
+    }
+
+    pub fn view(&self, config: &Config) -> Element<'_, Message> {
+        let mut column = Column::new()
+            .spacing(10)
+            .width(500)
+            .push(text("Settings").size(20))
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Alignment::Center)
+                    .push(text("Board size").width(150))
+                    .push(pick_list(
+                        &Config::BOARD_SIZES[..],
+                        Some(config.board_size),
+                        Message::BoardSizeSelected,
+                    )),
+            )
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .align_items(Alignment::Center)
+                    .push(text("Theme").width(150))
+                    .push(pick_list(
+                        &ThemeChoice::ALL[..],
+                        Some(config.theme),
+                        Message::ThemeSelected,
+                    )),
+            )
+            .push(checkbox(
+                "Antialiasing (applies after a restart)",
+                config.antialiasing,
+                Message::AntialiasingToggled,
+            ));
+
+        column = BoardColorKind::ALL
+            .into_iter()
+            .zip(&self.color_inputs)
+            .fold(column, |column, (kind, input)| {
+                let swatch = svg::Handle::from_memory(format!(
+                    r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 1 1"><rect width="1" height="1" fill="{}"/></svg>"#,
+                    config.colors.get(kind)
+                )
+                .into_bytes());
+                column.push(
+                    Row::new()
+                        .spacing(5)
+                        .align_items(Alignment::Center)
+                        .push(text(kind.to_string()).width(150))
+                        .push(
+                            text_input("#RRGGBB", input, move |input| {
+                                Message::BoardColorChanged(kind, input)
+                            })
+                            .width(100),
+                        )
+                        .push(svg(swatch).width(24).height(24)),
+                )
+            });
+
+        if let Some(ref error) = self.error {
+            column = column.push(text(error).style(Color::from_rgb8(0xC6, 0x28, 0x28)));
+        }
+        if let Some(ref path) = self.path {
+            column = column.push(text(format!("Saved to {}", path.display())).size(16));
+        }
+        column
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .push(button(text("Reset to defaults")).on_press(Message::ResetSettings))
+                    .push(button(text("Close")).on_press(Message::ToggleSettings)),
+            )
+            .into()
+    }
+
+    fn color_inputs_of(config: &Config) -> Vec<String> {
+        BoardColorKind::ALL
+            .iter()
+            .map(|kind| config.colors.get(*kind).to_string())
+            .collect()
+    }
+}
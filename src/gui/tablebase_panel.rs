@@ -0,0 +1,162 @@
+use iced::widget::{button, scrollable, text, text_input, Column, Row};
+use iced::{Alignment, Color, Element};
+use iced_chessboard::san::San;
+use pleco::Board;
+use shakmaty_syzygy::Wdl;
+
+use super::Message;
+use crate::tablebase::{self, ProbedMove, SyzygyDirectory, TableCoverage};
+
+pub struct TablebasePanel {
+    directory_path: String,
+    directory: Option<SyzygyDirectory>,
+    error: Option<String>,
+    /// The moves of the current position, probed when the position changes rather than on
+    /// every redraw.
+    probed_moves: Vec<ProbedMove>,
+}
+
+impl TablebasePanel {
+    pub fn new(directory_path: &str) -> Self {
+        let mut panel = Self {
+            directory_path: directory_path.to_string(),
+            directory: None,
+            error: None,
+            probed_moves: Vec::new(),
+        };
+        panel.load(&Board::start_pos());
+        panel
+    }
+
+    pub fn directory_path(&self) -> &str {
+        self.directory_path.trim()
+    }
+
+    pub fn set_directory_path(&mut self, directory_path: String) {
+        self.directory_path = directory_path;
+    }
+
+    /// Returns false when the directory could not be read.
+    pub fn load(&mut self, board: &Board) -> bool {
+        let directory_path = self.directory_path.trim();
+        self.directory = None;
+        self.error = None;
+        if !directory_path.is_empty() {
+            match SyzygyDirectory::scan(directory_path) {
+                Ok(directory) => self.directory = Some(directory),
+                Err(err) => {
+                    self.error = Some(format!("Could not read {}: {}", directory_path, err));
+                }
+            }
+        }
+        self.probe(board);
+        self.error.is_none()
+    }
+
+    pub fn probe(&mut self, board: &Board) {
+        self.probed_moves = match self.directory {
+            Some(ref directory) if directory.coverage(board).is_some() => {
+                directory.probe_moves(board)
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    pub fn view(&self, board: &Board) -> Element<'_, Message> {
+        let controls = Row::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(
+                text_input(
+                    "Syzygy tables directory",
+                    &self.directory_path,
+                    Message::TablebasePathChanged,
+                )
+                .on_submit(Message::LoadTablebases)
+                .width(200),
+            )
+            .push(button(text("Load tables")).on_press(Message::LoadTablebases));
+
+        let mut column = Column::new()
+            .spacing(5)
+            .width(300)
+            .push(text("Endgame tablebases").size(20))
+            .push(controls);
+
+        if let Some(ref error) = self.error {
+            return column
+                .push(text(error).style(Color::from_rgb8(0xC6, 0x28, 0x28)))
+                .into();
+        }
+        let directory = match self.directory {
+            Some(ref directory) => directory,
+            None => return column.into(),
+        };
+        column = column.push(
+            text(format!(
+                "{} WDL and {} DTZ tables found",
+                directory.wdl_count(),
+                directory.dtz_count()
+            ))
+            .size(16),
+        );
+
+        let coverage = match directory.coverage(board) {
+            Some(coverage) => coverage,
+            None => {
+                return column
+                    .push(text(format!(
+                        "Tables cover positions with at most {} pieces",
+                        tablebase::MAX_PIECES
+                    )))
+                    .into()
+            }
+        };
+        column = column.push(text(format!(
+            "{}: {}",
+            SyzygyDirectory::material_key(board, board.turn()),
+            TablebasePanel::coverage_label(coverage)
+        )));
+
+        let moves =
+            self.probed_moves
+                .iter()
+                .fold(Column::new().spacing(2), |moves_column, probed_move| {
+                    let result = match (probed_move.wdl, probed_move.dtz) {
+                        (Some(wdl), Some(dtz)) => {
+                            format!("{}  DTZ {}", TablebasePanel::wdl_label(wdl), dtz)
+                        }
+                        (Some(wdl), None) => TablebasePanel::wdl_label(wdl).to_string(),
+                        (None, _) => String::from("not in the tables"),
+                    };
+                    moves_column.push(
+                        text(format!(
+                            "{}  {}",
+                            San::from_move(board, probed_move.pleco_move),
+                            result
+                        ))
+                        .size(16),
+                    )
+                });
+        column.push(scrollable(moves).height(150)).into()
+    }
+
+    fn wdl_label(wdl: Wdl) -> &'static str {
+        match wdl {
+            Wdl::Win => "Win",
+            Wdl::CursedWin => "Win, drawn by the fifty-move rule",
+            Wdl::Draw => "Draw",
+            Wdl::BlessedLoss => "Loss, drawn by the fifty-move rule",
+            Wdl::Loss => "Loss",
+        }
+    }
+
+    fn coverage_label(coverage: TableCoverage) -> &'static str {
+        match (coverage.wdl, coverage.dtz) {
+            (true, true) => "WDL and DTZ tables found",
+            (true, false) => "WDL table found, DTZ table missing",
+            (false, true) => "DTZ table found, WDL table missing",
+            (false, false) => "no table",
+        }
+    }
+}
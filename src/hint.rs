@@ -0,0 +1,32 @@
+use pleco::bots::AlphaBetaSearcher;
+use pleco::tools::Searcher;
+use pleco::{Board, File, SQ};
+
+const HINT_DEPTH: u16 = 4;
+pub const HINTS_PER_GAME: u8 = 3;
+
+pub struct Hint {}
+
+impl Hint {
+    pub async fn search(board: Board) -> Option<(SQ, SQ)> {
+        if board.generate_moves().is_empty() {
+            return None;
+        }
+        let best_move =
+            tokio::task::spawn_blocking(move || AlphaBetaSearcher::best_move(board, HINT_DEPTH))
+                .await
+                .ok()?;
+        if best_move.is_null() {
+            return None;
+        }
+        let src = best_move.get_src();
+        let dest = if best_move.is_king_castle() {
+            SQ::make(File::G, src.rank())
+        } else if best_move.is_queen_castle() {
+            SQ::make(File::C, src.rank())
+        } else {
+            best_move.get_dest()
+        };
+        Some((src, dest))
+    }
+}
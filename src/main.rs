@@ -1,10 +1,56 @@
+mod cli;
+mod config;
+mod eco;
+mod game_clock;
+mod game_controller;
+mod game_database;
+mod game_record;
 mod gui;
-use gui::App;
-use iced::{Settings, Application, window};
+mod hint;
+mod pgn;
+mod polyglot;
+mod position_search;
+mod post_game;
+mod tablebase;
+mod terminal;
+mod uci;
+use clap::Parser;
+use cli::{Cli, ColorArg};
+use config::Config;
+use gui::{App, Flags, LaunchOptions};
+use iced::{window, Application, Settings};
+use terminal::Terminal;
 
 fn main() -> iced::Result {
+    let cli = Cli::parse();
+    if cli.terminal {
+        run_terminal(&cli);
+        return Ok(());
+    }
+    let loaded_config = match cli.config {
+        Some(ref path) => Config::load_from(path),
+        None => Config::load(),
+    };
+    let (config, startup_error) = match loaded_config {
+        Ok(config) => (config, None),
+        Err(err) => (
+            Config::default(),
+            Some(format!("The configuration file could not be read: {}", err)),
+        ),
+    };
     App::run(Settings {
-        antialiasing: true,
+        antialiasing: config.antialiasing,
+        flags: Flags {
+            config,
+            config_path: cli.config,
+            startup_error,
+            launch: LaunchOptions {
+                fen: cli.fen,
+                pgn_path: cli.pgn,
+                play_black: cli.color.map(|color| color == ColorArg::Black),
+                time_control: cli.time_control,
+            },
+        },
         window: window::Settings {
             position: window::Position::Centered,
             ..window::Settings::default()
@@ -12,3 +58,33 @@ fn main() -> iced::Result {
         ..Settings::default()
     })
 }
+
+fn run_terminal(cli: &Cli) {
+    let unsupported: Vec<&str> = [
+        ("--time-control", cli.time_control.is_some()),
+        ("--config", cli.config.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(flag, given)| given.then_some(flag))
+    .collect();
+    if !unsupported.is_empty() {
+        eprintln!(
+            "{} cannot be used with --terminal",
+            unsupported.join(" and ")
+        );
+        std::process::exit(2);
+    }
+    let black_at_bottom = cli.color == Some(ColorArg::Black);
+    let terminal = match (cli.fen.as_ref(), cli.pgn.as_ref()) {
+        (Some(fen), _) => Terminal::from_fen(fen, black_at_bottom),
+        (None, Some(path)) => std::fs::read_to_string(path)
+            .map_err(|err| format!("Could not read {}: {}", path.display(), err))
+            .and_then(|pgn| Terminal::from_pgn(&pgn, black_at_bottom)),
+        (None, None) => Ok(Terminal::new(black_at_bottom)),
+    };
+    let result = terminal.and_then(|mut terminal| terminal.run().map_err(|err| err.to_string()));
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
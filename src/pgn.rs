@@ -0,0 +1,259 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnToken {
+    Move(String),
+    Nag(u8),
+    Comment(String),
+    VariationStart,
+    VariationEnd,
+    Result(String),
+}
+
+pub struct Pgn {}
+
+impl Pgn {
+    pub fn tags(pgn: &str) -> Vec<(String, String)> {
+        pgn.lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with('[') && line.ends_with(']'))
+            .filter_map(|line| {
+                let line = &line[1..line.len() - 1];
+                let (name, value) = line.split_once(char::is_whitespace)?;
+                let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+                Some((name.to_string(), value.replace("\\\"", "\"")))
+            })
+            .collect()
+    }
+
+    pub fn movetext(pgn: &str) -> String {
+        pgn.lines()
+            .filter(|line| !line.trim_start().starts_with('['))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn tokens(movetext: &str) -> Vec<PgnToken> {
+        let mut tokens = vec![];
+        let mut chars = movetext.chars().peekable();
+        while let Some(current) = chars.next() {
+            match current {
+                '{' => {
+                    let comment: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                    tokens.push(PgnToken::Comment(
+                        comment.split_whitespace().collect::<Vec<_>>().join(" "),
+                    ));
+                }
+                ';' => {
+                    let comment: String = chars.by_ref().take_while(|c| *c != '\n').collect();
+                    let comment = Pgn::comment_text(&comment);
+                    tokens.push(PgnToken::Comment(
+                        comment.split_whitespace().collect::<Vec<_>>().join(" "),
+                    ));
+                }
+                '(' => tokens.push(PgnToken::VariationStart),
+                ')' => tokens.push(PgnToken::VariationEnd),
+                current if current.is_whitespace() => {}
+                current => {
+                    let mut word = String::from(current);
+                    while let Some(next) = chars.peek() {
+                        if next.is_whitespace() || "{};()".contains(*next) {
+                            break;
+                        }
+                        word.push(*next);
+                        chars.next();
+                    }
+                    tokens.extend(Pgn::word_tokens(&word));
+                }
+            }
+        }
+        tokens
+    }
+
+    /// Comments are written between braces, which offer no escape for a closing brace, so
+    /// it is left out of comment text wherever a comment enters a game record.
+    pub fn comment_text(text: &str) -> String {
+        text.replace('}', "")
+    }
+
+    pub fn glyph_nag(glyph: &str) -> Option<u8> {
+        match glyph {
+            "!" => Some(1),
+            "?" => Some(2),
+            "!!" => Some(3),
+            "??" => Some(4),
+            "!?" => Some(5),
+            "?!" => Some(6),
+            "=" => Some(10),
+            "∞" => Some(13),
+            "⩲" | "+/=" => Some(14),
+            "⩱" | "=/+" => Some(15),
+            "±" | "+/-" => Some(16),
+            "∓" | "-/+" => Some(17),
+            "+−" | "+-" => Some(18),
+            "−+" | "-+" => Some(19),
+            _ => None,
+        }
+    }
+
+    pub fn nag_glyph(nag: u8) -> Option<&'static str> {
+        match nag {
+            1 => Some("!"),
+            2 => Some("?"),
+            3 => Some("!!"),
+            4 => Some("??"),
+            5 => Some("!?"),
+            6 => Some("?!"),
+            10 => Some("="),
+            13 => Some("∞"),
+            14 => Some("⩲"),
+            15 => Some("⩱"),
+            16 => Some("±"),
+            17 => Some("∓"),
+            18 => Some("+−"),
+            19 => Some("−+"),
+            _ => None,
+        }
+    }
+
+    pub fn same_nag_group(nag: u8, other: u8) -> bool {
+        // Move assessments ($1-$9) and position assessments ($10-$135) each keep one glyph.
+        let group = |nag: u8| match nag {
+            1..=9 => Some(0),
+            10..=135 => Some(1),
+            _ => None,
+        };
+        group(nag).is_some() && group(nag) == group(other)
+    }
+
+    fn word_tokens(word: &str) -> Vec<PgnToken> {
+        if let Some(nag) = word.strip_prefix('$') {
+            return nag.parse().map(PgnToken::Nag).into_iter().collect();
+        }
+        if ["1-0", "0-1", "1/2-1/2", "*"].contains(&word) {
+            return vec![PgnToken::Result(word.to_string())];
+        }
+        if let Some(nag) = Pgn::glyph_nag(word) {
+            return vec![PgnToken::Nag(nag)];
+        }
+        // Move numbers may be glued to the move, as in "12.Nf3" or "12...Nf6".
+        let without_number = word.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+        let word = if word[..word.len() - without_number.len()].contains('.') {
+            without_number
+        } else {
+            word
+        };
+        if word.is_empty() {
+            return vec![];
+        }
+        let san = word.trim_end_matches(['!', '?']);
+        let mut tokens = vec![PgnToken::Move(san.to_string())];
+        if let Some(nag) = Pgn::glyph_nag(&word[san.len()..]) {
+            tokens.push(PgnToken::Nag(nag));
+        }
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(sans: &[&str]) -> Vec<PgnToken> {
+        sans.iter()
+            .map(|san| PgnToken::Move(san.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_tags() {
+        let pgn = "[Event \"Casual \\\"blitz\\\"\"]\n[White \"Ann\"]\n\n1. e4 *";
+        assert_eq!(
+            Pgn::tags(pgn),
+            vec![
+                (String::from("Event"), String::from("Casual \"blitz\"")),
+                (String::from("White"), String::from("Ann")),
+            ]
+        );
+        assert_eq!(Pgn::movetext(pgn).trim(), "1. e4 *");
+    }
+
+    #[test]
+    fn move_numbers_may_be_glued_to_moves() {
+        assert_eq!(Pgn::tokens("1.e4 e5 2.Nf3"), moves(&["e4", "e5", "Nf3"]));
+        assert_eq!(Pgn::tokens("12...Nf6 13. O-O"), moves(&["Nf6", "O-O"]));
+        assert_eq!(Pgn::tokens("1. e4 1... e5"), moves(&["e4", "e5"]));
+    }
+
+    #[test]
+    fn reads_brace_and_line_comments() {
+        assert_eq!(
+            Pgn::tokens("e4 {Best  by\n test} e5 ; the rest of the line\nNf3"),
+            vec![
+                PgnToken::Move(String::from("e4")),
+                PgnToken::Comment(String::from("Best by test")),
+                PgnToken::Move(String::from("e5")),
+                PgnToken::Comment(String::from("the rest of the line")),
+                PgnToken::Move(String::from("Nf3")),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_nags_and_glyph_suffixes() {
+        assert_eq!(
+            Pgn::tokens("e4!? e5 $2 Nf3?! Nc6 !! Bb5+! $14"),
+            vec![
+                PgnToken::Move(String::from("e4")),
+                PgnToken::Nag(5),
+                PgnToken::Move(String::from("e5")),
+                PgnToken::Nag(2),
+                PgnToken::Move(String::from("Nf3")),
+                PgnToken::Nag(6),
+                PgnToken::Move(String::from("Nc6")),
+                PgnToken::Nag(3),
+                PgnToken::Move(String::from("Bb5+")),
+                PgnToken::Nag(1),
+                PgnToken::Nag(14),
+            ]
+        );
+    }
+
+    #[test]
+    fn glyphs_and_nags_convert_both_ways() {
+        for nag in (1..=6).chain(10..=10).chain(13..=19) {
+            let glyph = Pgn::nag_glyph(nag).unwrap();
+            assert_eq!(Pgn::glyph_nag(glyph), Some(nag));
+        }
+        assert_eq!(Pgn::glyph_nag("+/-"), Some(16));
+        assert_eq!(Pgn::nag_glyph(146), None);
+    }
+
+    #[test]
+    fn reads_variations_and_results() {
+        assert_eq!(
+            Pgn::tokens("1. e4 (1. d4 d5) 1... e5 1/2-1/2"),
+            vec![
+                PgnToken::Move(String::from("e4")),
+                PgnToken::VariationStart,
+                PgnToken::Move(String::from("d4")),
+                PgnToken::Move(String::from("d5")),
+                PgnToken::VariationEnd,
+                PgnToken::Move(String::from("e5")),
+                PgnToken::Result(String::from("1/2-1/2")),
+            ]
+        );
+        for result in ["1-0", "0-1", "*"] {
+            assert_eq!(
+                Pgn::tokens(result),
+                vec![PgnToken::Result(result.to_string())]
+            );
+        }
+    }
+
+    #[test]
+    fn move_assessments_and_position_assessments_are_separate_groups() {
+        assert!(Pgn::same_nag_group(1, 6));
+        assert!(Pgn::same_nag_group(10, 19));
+        assert!(!Pgn::same_nag_group(1, 14));
+        assert!(!Pgn::same_nag_group(140, 140));
+    }
+}
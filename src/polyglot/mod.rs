@@ -0,0 +1,257 @@
+mod random64;
+use random64::RANDOM_64;
+
+use std::fs;
+use std::io;
+
+use iced_chessboard::position_setup::CastlingRight;
+use pleco::core::sq::NO_SQ;
+use pleco::{Board, Piece, Player, SQ};
+
+const ENTRY_SIZE: usize = 16;
+const CASTLING_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BookEntry {
+    key: u64,
+    raw_move: u16,
+    weight: u16,
+    learn: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookMove {
+    pub uci: String,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+pub struct PolyglotBook {
+    entries: Vec<BookEntry>,
+}
+
+impl PolyglotBook {
+    pub fn load(path: &str) -> io::Result<Self> {
+        PolyglotBook::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let chunks = bytes.chunks_exact(ENTRY_SIZE);
+        if !chunks.remainder().is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the file size is not a multiple of 16 bytes",
+            ));
+        }
+        let mut entries: Vec<BookEntry> = chunks
+            .map(|chunk| BookEntry {
+                key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+                raw_move: u16::from_be_bytes(chunk[8..10].try_into().unwrap()),
+                weight: u16::from_be_bytes(chunk[10..12].try_into().unwrap()),
+                learn: u32::from_be_bytes(chunk[12..16].try_into().unwrap()),
+            })
+            .collect();
+        // The sort is stable, so unsorted books keep their move order within a position.
+        entries.sort_by_key(|entry| entry.key);
+        Ok(Self { entries })
+    }
+
+    pub fn moves(
+        &self,
+        board: &Board,
+        castling_rights: [bool; 4],
+        chess960: bool,
+    ) -> Vec<BookMove> {
+        let key = PolyglotBook::key(board, castling_rights);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let mut moves: Vec<BookMove> = self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .map(|entry| BookMove {
+                uci: PolyglotBook::decode_move(board, entry.raw_move, chess960),
+                weight: entry.weight,
+                learn: entry.learn,
+            })
+            .collect();
+        moves.sort_by_key(|book_move| std::cmp::Reverse(book_move.weight));
+        moves
+    }
+
+    pub fn key(board: &Board, castling_rights: [bool; 4]) -> u64 {
+        let mut key = 0u64;
+        (0..64u8).for_each(|square| {
+            if let Some(kind) = PolyglotBook::piece_kind(board.piece_at_sq(SQ(square))) {
+                key ^= RANDOM_64[64 * kind + square as usize];
+            }
+        });
+        CastlingRight::ALL.into_iter().for_each(|right| {
+            if castling_rights[right.index()] {
+                key ^= RANDOM_64[CASTLING_OFFSET + right.index()];
+            }
+        });
+        if let Some(file) = PolyglotBook::en_passant_file(board) {
+            key ^= RANDOM_64[EN_PASSANT_OFFSET + file as usize];
+        }
+        if board.turn() == Player::White {
+            key ^= RANDOM_64[TURN_OFFSET];
+        }
+        key
+    }
+
+    fn piece_kind(piece: Piece) -> Option<usize> {
+        match piece {
+            Piece::BlackPawn => Some(0),
+            Piece::WhitePawn => Some(1),
+            Piece::BlackKnight => Some(2),
+            Piece::WhiteKnight => Some(3),
+            Piece::BlackBishop => Some(4),
+            Piece::WhiteBishop => Some(5),
+            Piece::BlackRook => Some(6),
+            Piece::WhiteRook => Some(7),
+            Piece::BlackQueen => Some(8),
+            Piece::WhiteQueen => Some(9),
+            Piece::BlackKing => Some(10),
+            Piece::WhiteKing => Some(11),
+            Piece::None => None,
+        }
+    }
+
+    fn en_passant_file(board: &Board) -> Option<u8> {
+        let en_passant = board.ep_square();
+        if en_passant == NO_SQ {
+            return None;
+        }
+        let file = en_passant.file_idx_of_sq();
+        let (pawn, pawn_rank) = match board.turn() {
+            Player::White => (Piece::WhitePawn, 4u8),
+            Player::Black => (Piece::BlackPawn, 3u8),
+        };
+        let can_capture = [file.checked_sub(1), Some(file + 1).filter(|file| *file < 8)]
+            .into_iter()
+            .flatten()
+            .any(|capture_file| board.piece_at_sq(SQ(pawn_rank * 8 + capture_file)) == pawn);
+        if can_capture {
+            Some(file)
+        } else {
+            None
+        }
+    }
+
+    fn decode_move(board: &Board, raw_move: u16, chess960: bool) -> String {
+        let square = |file: u16, rank: u16| SQ((rank * 8 + file) as u8);
+        let dest = square(raw_move & 7, (raw_move >> 3) & 7);
+        let src = square((raw_move >> 6) & 7, (raw_move >> 9) & 7);
+        let promotion = match (raw_move >> 12) & 7 {
+            1 => "n",
+            2 => "b",
+            3 => "r",
+            4 => "q",
+            _ => "",
+        };
+
+        // Polyglot encodes castling as the king capturing its own rook.
+        let moved_piece = board.piece_at_sq(src);
+        let is_king = moved_piece == Piece::WhiteKing || moved_piece == Piece::BlackKing;
+        let dest = if !chess960 && is_king && src.file_idx_of_sq() == 4 {
+            match (dest.file_idx_of_sq(), board.piece_at_sq(dest)) {
+                (7, Piece::WhiteRook) | (7, Piece::BlackRook) => SQ(dest.0 - 1),
+                (0, Piece::WhiteRook) | (0, Piece::BlackRook) => SQ(dest.0 + 2),
+                _ => dest,
+            }
+        } else {
+            dest
+        };
+        format!("{}{}{}", src, dest, promotion)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START_KEY: u64 = 0x463b96181691fc9c;
+    const ALL_CASTLING: [bool; 4] = [true; 4];
+
+    fn board(fen: &str) -> Board {
+        Board::from_fen(fen).unwrap()
+    }
+
+    fn entry(key: u64, raw_move: u16, weight: u16) -> Vec<u8> {
+        let mut bytes = key.to_be_bytes().to_vec();
+        bytes.extend(raw_move.to_be_bytes());
+        bytes.extend(weight.to_be_bytes());
+        bytes.extend(0u32.to_be_bytes());
+        bytes
+    }
+
+    /// Encodes a move the way Polyglot books store it.
+    fn raw_move(src: (u16, u16), dest: (u16, u16)) -> u16 {
+        dest.0 | (dest.1 << 3) | (src.0 << 6) | (src.1 << 9)
+    }
+
+    #[test]
+    fn start_position_key() {
+        assert_eq!(
+            PolyglotBook::key(&Board::start_pos(), ALL_CASTLING),
+            START_KEY
+        );
+    }
+
+    #[test]
+    fn keys_after_moves() {
+        let after_e4 = board("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+        assert_eq!(
+            PolyglotBook::key(&after_e4, ALL_CASTLING),
+            0x823c9b50fd114196
+        );
+        // The en passant file only counts when a pawn can take.
+        let after_e4_d5_e5_f5 =
+            board("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3");
+        assert_eq!(
+            PolyglotBook::key(&after_e4_d5_e5_f5, ALL_CASTLING),
+            0x22a48b5a8e47ff78
+        );
+    }
+
+    #[test]
+    fn finds_the_moves_of_a_position_in_an_unsorted_book() {
+        let e2e4 = raw_move((4, 1), (4, 3));
+        let d2d4 = raw_move((3, 1), (3, 3));
+        let bytes: Vec<u8> = [
+            entry(START_KEY + 1, e2e4, 99),
+            entry(START_KEY, e2e4, 10),
+            entry(1, d2d4, 99),
+            entry(START_KEY, d2d4, 20),
+            entry(u64::MAX, e2e4, 99),
+        ]
+        .concat();
+        let book = PolyglotBook::from_bytes(&bytes).unwrap();
+
+        let moves = book.moves(&Board::start_pos(), ALL_CASTLING, false);
+        let moves: Vec<(&str, u16)> = moves
+            .iter()
+            .map(|book_move| (book_move.uci.as_str(), book_move.weight))
+            .collect();
+        assert_eq!(moves, vec![("d2d4", 20), ("e2e4", 10)]);
+
+        let after_e4 = board("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+        assert!(book.moves(&after_e4, ALL_CASTLING, false).is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_books() {
+        assert!(PolyglotBook::from_bytes(&[0; 17]).is_err());
+    }
+
+    #[test]
+    fn decodes_castling_as_king_takes_rook() {
+        let board = board("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let short = raw_move((4, 0), (7, 0));
+        let long = raw_move((4, 0), (0, 0));
+        assert_eq!(PolyglotBook::decode_move(&board, short, false), "e1g1");
+        assert_eq!(PolyglotBook::decode_move(&board, long, false), "e1c1");
+        assert_eq!(PolyglotBook::decode_move(&board, short, true), "e1h1");
+    }
+}
@@ -0,0 +1,203 @@
+use pleco::{Board, Piece, Player, SQ};
+
+use crate::game_database::StoredGame;
+use crate::game_record::GameRecord;
+use crate::tablebase::SyzygyDirectory;
+
+pub enum PositionQuery {
+    Position(u64),
+    Pattern(PatternQuery),
+}
+
+/// A material signature such as "KRPvKR" (White first) and/or pieces on given squares such as
+/// "Ne5 pd6", uppercase for White and lowercase for Black.
+pub struct PatternQuery {
+    material: Option<String>,
+    pieces: Vec<(Piece, SQ)>,
+}
+
+impl PatternQuery {
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut material = None;
+        let mut pieces = vec![];
+        for term in query.split(|c: char| c.is_whitespace() || c == ',') {
+            if term.is_empty() {
+                continue;
+            }
+            if let Some(piece_square) = PatternQuery::parse_piece_square(term) {
+                pieces.push(piece_square);
+            } else if PatternQuery::is_material(term) {
+                material = Some(term.to_uppercase().replace('V', "v"));
+            } else {
+                return Err(format!("Unknown search term {}", term));
+            }
+        }
+        if material.is_none() && pieces.is_empty() {
+            return Err(String::from("The search query is empty"));
+        }
+        Ok(Self { material, pieces })
+    }
+
+    pub fn matches(&self, board: &Board) -> bool {
+        let material_matches = self.material.as_ref().is_none_or(|material| {
+            SyzygyDirectory::material_key(board, Player::White) == *material
+        });
+        material_matches
+            && self
+                .pieces
+                .iter()
+                .all(|(piece, square)| board.piece_at_sq(*square) == *piece)
+    }
+
+    fn is_material(term: &str) -> bool {
+        match term.to_uppercase().split_once('V') {
+            Some((white, black)) => [white, black]
+                .iter()
+                .all(|side| side.starts_with('K') && side.chars().all(|c| "KQRBNP".contains(c))),
+            None => false,
+        }
+    }
+
+    fn parse_piece_square(term: &str) -> Option<(Piece, SQ)> {
+        let mut chars = term.chars();
+        let piece = match chars.next()? {
+            'P' => Piece::WhitePawn,
+            'N' => Piece::WhiteKnight,
+            'B' => Piece::WhiteBishop,
+            'R' => Piece::WhiteRook,
+            'Q' => Piece::WhiteQueen,
+            'K' => Piece::WhiteKing,
+            'p' => Piece::BlackPawn,
+            'n' => Piece::BlackKnight,
+            'b' => Piece::BlackBishop,
+            'r' => Piece::BlackRook,
+            'q' => Piece::BlackQueen,
+            'k' => Piece::BlackKing,
+            _ => return None,
+        };
+        let file = chars.next().filter(|file| ('a'..='h').contains(file))?;
+        let rank = chars.next().filter(|rank| ('1'..='8').contains(rank))?;
+        if chars.next().is_some() {
+            return None;
+        }
+        Some((piece, SQ((rank as u8 - b'1') * 8 + (file as u8 - b'a'))))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MoveStatistics {
+    pub san: String,
+    pub games: usize,
+    pub white_wins: usize,
+    pub draws: usize,
+    pub black_wins: usize,
+}
+
+impl MoveStatistics {
+    pub fn white_score(&self) -> f64 {
+        let decided = self.white_wins + self.draws + self.black_wins;
+        if decided == 0 {
+            return 0.0;
+        }
+        100.0 * (self.white_wins as f64 + self.draws as f64 / 2.0) / decided as f64
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    pub games: Vec<usize>,
+    pub moves: Vec<MoveStatistics>,
+}
+
+#[derive(Debug, Clone)]
+struct IndexedPosition {
+    board: Board,
+    next_san: Option<String>,
+}
+
+/// The main line positions of every stored game, replayed once so that searches stay cheap.
+#[derive(Debug, Clone)]
+pub struct PositionIndex {
+    games: Vec<Vec<IndexedPosition>>,
+}
+
+impl PositionIndex {
+    pub fn build(games: &[StoredGame]) -> Self {
+        let mut index = Self { games: vec![] };
+        games.iter().for_each(|game| index.add(game));
+        index
+    }
+
+    /// Replays the games on a blocking thread, since a large database takes a while.
+    pub async fn build_in_background(games: Vec<StoredGame>) -> Option<Self> {
+        tokio::task::spawn_blocking(move || PositionIndex::build(&games))
+            .await
+            .ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    pub fn add(&mut self, game: &StoredGame) {
+        // Games that cannot be replayed any more still take a slot to keep indices aligned.
+        let positions = match GameRecord::from_pgn(&game.pgn) {
+            Ok(record) => {
+                let next_moves = record
+                    .moves()
+                    .iter()
+                    .map(|recorded_move| Some(recorded_move.san.clone()));
+                record
+                    .positions()
+                    .into_iter()
+                    .zip(next_moves.chain(std::iter::once(None)))
+                    .map(|(board, next_san)| IndexedPosition { board, next_san })
+                    .collect()
+            }
+            Err(_) => vec![],
+        };
+        self.games.push(positions);
+    }
+
+    /// Counts every game once, at its first position matching the query.
+    pub fn search(&self, games: &[StoredGame], query: &PositionQuery) -> SearchResults {
+        let mut results = SearchResults::default();
+        for (index, positions) in self.games.iter().enumerate() {
+            let found = positions.iter().find(|position| match query {
+                PositionQuery::Position(key) => position.board.zobrist() == *key,
+                PositionQuery::Pattern(pattern) => pattern.matches(&position.board),
+            });
+            let (position, game) = match (found, games.get(index)) {
+                (Some(position), Some(game)) => (position, game),
+                _ => continue,
+            };
+            results.games.push(index);
+            let san = match position.next_san {
+                Some(ref san) => san.clone(),
+                None => continue,
+            };
+            let statistics = match results.moves.iter().position(|stats| stats.san == san) {
+                Some(existing) => &mut results.moves[existing],
+                None => {
+                    results.moves.push(MoveStatistics {
+                        san,
+                        ..MoveStatistics::default()
+                    });
+                    results.moves.last_mut().unwrap()
+                }
+            };
+            statistics.games += 1;
+            match game.result.as_str() {
+                "1-0" => statistics.white_wins += 1,
+                "0-1" => statistics.black_wins += 1,
+                "1/2-1/2" => statistics.draws += 1,
+                _ => {}
+            }
+        }
+        results.games.reverse();
+        results
+            .moves
+            .sort_by_key(|statistics| std::cmp::Reverse(statistics.games));
+        results
+    }
+}
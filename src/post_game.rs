@@ -0,0 +1,315 @@
+use std::fmt;
+
+use iced_chessboard::san::San;
+use pleco::bots::alphabeta::alpha_beta_search;
+use pleco::core::score::{INFINITE, MATE_IN_MAX_PLY, NEG_INFINITE};
+use pleco::{Board, Player};
+
+use crate::game_record::GameRecord;
+use crate::uci::{Score, SearchLimit, UciEngine};
+
+const MATE_CENTIPAWNS: i32 = 10_000;
+const CENTIPAWNS_CAP: i32 = 1_000;
+const ENGINE_REVIEW_DEPTH: u32 = 12;
+const BUILT_IN_REVIEW_DEPTH: u16 = 3;
+const WIN_PROBABILITY_FACTOR: f64 = 0.003_682_08;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Judgement {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Judgement {
+    fn from_centipawn_loss(centipawn_loss: i32) -> Option<Self> {
+        match centipawn_loss {
+            loss if loss >= 300 => Some(Judgement::Blunder),
+            loss if loss >= 100 => Some(Judgement::Mistake),
+            loss if loss >= 50 => Some(Judgement::Inaccuracy),
+            _ => None,
+        }
+    }
+
+    pub fn nag(self) -> u8 {
+        match self {
+            Judgement::Inaccuracy => 6,
+            Judgement::Mistake => 2,
+            Judgement::Blunder => 4,
+        }
+    }
+
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Judgement::Inaccuracy => "?!",
+            Judgement::Mistake => "?",
+            Judgement::Blunder => "??",
+        }
+    }
+}
+
+impl fmt::Display for Judgement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Judgement::Inaccuracy => write!(f, "Inaccuracy"),
+            Judgement::Mistake => write!(f, "Mistake"),
+            Judgement::Blunder => write!(f, "Blunder"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PositionEvaluation {
+    centipawns: i32,
+    best_move: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MoveReview {
+    pub centipawn_loss: i32,
+    pub judgement: Option<Judgement>,
+    pub best_move: Option<String>,
+}
+
+impl MoveReview {
+    pub fn comment(&self) -> Option<String> {
+        let judgement = self.judgement?;
+        Some(match self.best_move {
+            Some(ref best_move) => format!("{}. {} was best.", judgement, best_move),
+            None => format!("{}.", judgement),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlayerSummary {
+    pub accuracy: f64,
+    pub average_centipawn_loss: f64,
+    pub inaccuracies: usize,
+    pub mistakes: usize,
+    pub blunders: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct GameReview {
+    moves: Vec<MoveReview>,
+    white: PlayerSummary,
+    black: PlayerSummary,
+}
+
+impl GameReview {
+    pub fn moves(&self) -> &[MoveReview] {
+        &self.moves
+    }
+
+    pub fn summary(&self, player: Player) -> &PlayerSummary {
+        match player {
+            Player::White => &self.white,
+            Player::Black => &self.black,
+        }
+    }
+
+    pub fn annotate(&self, record: &mut GameRecord) {
+        self.moves
+            .iter()
+            .enumerate()
+            .for_each(|(index, move_review)| {
+                if let (Some(judgement), Some(comment)) =
+                    (move_review.judgement, move_review.comment())
+                {
+                    record.annotate(index, judgement.nag(), &comment);
+                }
+            });
+    }
+
+    pub async fn run(record: GameRecord, engine_path: Option<String>) -> Result<Self, String> {
+        let positions = record.positions();
+        let evaluations = match engine_path {
+            Some(engine_path) => {
+                GameReview::evaluate_with_engine(&record, &positions, &engine_path)
+                    .await
+                    .map_err(|err| {
+                        format!("Could not review the game with {}: {}", engine_path, err)
+                    })?
+            }
+            None => GameReview::evaluate_built_in(positions.clone()).await?,
+        };
+        Ok(GameReview::from_evaluations(&positions, &evaluations))
+    }
+
+    fn from_evaluations(positions: &[Board], evaluations: &[PositionEvaluation]) -> Self {
+        let mut white_moves = vec![];
+        let mut black_moves = vec![];
+
+        let moves = positions
+            .iter()
+            .zip(evaluations.windows(2))
+            .map(|(position, evaluations)| {
+                let mover = position.turn();
+                let sign = if mover == Player::White { 1 } else { -1 };
+                let before = sign
+                    * evaluations[0]
+                        .centipawns
+                        .clamp(-CENTIPAWNS_CAP, CENTIPAWNS_CAP);
+                let after = sign
+                    * evaluations[1]
+                        .centipawns
+                        .clamp(-CENTIPAWNS_CAP, CENTIPAWNS_CAP);
+                let centipawn_loss = (before - after).max(0);
+                let judgement = Judgement::from_centipawn_loss(centipawn_loss);
+                let accuracy = GameReview::move_accuracy(before, after);
+                match mover {
+                    Player::White => white_moves.push((centipawn_loss, accuracy, judgement)),
+                    Player::Black => black_moves.push((centipawn_loss, accuracy, judgement)),
+                }
+                MoveReview {
+                    centipawn_loss,
+                    judgement,
+                    best_move: judgement.and(evaluations[0].best_move.clone()),
+                }
+            })
+            .collect();
+
+        Self {
+            moves,
+            white: GameReview::summarize(&white_moves),
+            black: GameReview::summarize(&black_moves),
+        }
+    }
+
+    fn summarize(moves: &[(i32, f64, Option<Judgement>)]) -> PlayerSummary {
+        if moves.is_empty() {
+            return PlayerSummary::default();
+        }
+        let count = |judgement| {
+            moves
+                .iter()
+                .filter(|(_, _, other)| *other == Some(judgement))
+                .count()
+        };
+        PlayerSummary {
+            accuracy: moves.iter().map(|(_, accuracy, _)| accuracy).sum::<f64>()
+                / moves.len() as f64,
+            average_centipawn_loss: moves.iter().map(|(loss, _, _)| loss).sum::<i32>() as f64
+                / moves.len() as f64,
+            inaccuracies: count(Judgement::Inaccuracy),
+            mistakes: count(Judgement::Mistake),
+            blunders: count(Judgement::Blunder),
+        }
+    }
+
+    fn move_accuracy(before: i32, after: i32) -> f64 {
+        let win_percent = |centipawns: i32| {
+            50.0 + 50.0 * (2.0 / (1.0 + (-WIN_PROBABILITY_FACTOR * centipawns as f64).exp()) - 1.0)
+        };
+        let win_percent_loss = (win_percent(before) - win_percent(after)).max(0.0);
+        (103.1668 * (-0.04354 * win_percent_loss).exp() - 3.1669).clamp(0.0, 100.0)
+    }
+
+    fn terminal_evaluation(position: &Board) -> Option<PositionEvaluation> {
+        if !position.generate_moves().is_empty() {
+            return None;
+        }
+        let centipawns = if position.in_check() {
+            GameReview::white_point_of_view(-MATE_CENTIPAWNS, position.turn())
+        } else {
+            0
+        };
+        Some(PositionEvaluation {
+            centipawns,
+            best_move: None,
+        })
+    }
+
+    async fn evaluate_with_engine(
+        record: &GameRecord,
+        positions: &[Board],
+        engine_path: &str,
+    ) -> std::io::Result<Vec<PositionEvaluation>> {
+        let mut engine = UciEngine::start(engine_path).await?;
+        let chess960 = if record.is_chess960() {
+            "true"
+        } else {
+            "false"
+        };
+        engine.set_option("UCI_Chess960", chess960).await?;
+        let uci_moves: Vec<String> = record
+            .moves()
+            .iter()
+            .map(|recorded_move| recorded_move.uci.clone())
+            .collect();
+
+        let mut evaluations = vec![];
+        for (index, position) in positions.iter().enumerate() {
+            if let Some(evaluation) = GameReview::terminal_evaluation(position) {
+                evaluations.push(evaluation);
+                continue;
+            }
+            engine.wait_ready().await?;
+            engine
+                .set_position(record.start_fen(), &uci_moves[..index])
+                .await?;
+            let (score, best_move) = engine
+                .evaluate(SearchLimit::Depth(ENGINE_REVIEW_DEPTH))
+                .await?;
+            let centipawns = match score {
+                Some(Score::Centipawns(centipawns)) => centipawns,
+                Some(Score::Mate(moves)) if moves > 0 => MATE_CENTIPAWNS,
+                Some(Score::Mate(_)) => -MATE_CENTIPAWNS,
+                None => 0,
+            };
+            evaluations.push(PositionEvaluation {
+                centipawns: GameReview::white_point_of_view(centipawns, position.turn()),
+                best_move: best_move
+                    .and_then(|best_move| San::from_uci_line(position, &[best_move]).pop()),
+            });
+        }
+        engine.quit().await;
+        Ok(evaluations)
+    }
+
+    async fn evaluate_built_in(positions: Vec<Board>) -> Result<Vec<PositionEvaluation>, String> {
+        tokio::task::spawn_blocking(move || {
+            positions
+                .iter()
+                .map(|position| {
+                    if let Some(evaluation) = GameReview::terminal_evaluation(position) {
+                        return evaluation;
+                    }
+                    let scoring_move = alpha_beta_search(
+                        &mut position.shallow_clone(),
+                        NEG_INFINITE as i16,
+                        INFINITE as i16,
+                        BUILT_IN_REVIEW_DEPTH,
+                    );
+                    let score = scoring_move.score as i32;
+                    let centipawns = if score >= MATE_IN_MAX_PLY {
+                        MATE_CENTIPAWNS
+                    } else if score <= -MATE_IN_MAX_PLY {
+                        -MATE_CENTIPAWNS
+                    } else {
+                        score
+                    };
+                    let best_move = if scoring_move.bit_move.is_null() {
+                        None
+                    } else {
+                        Some(San::from_move(position, scoring_move.bit_move))
+                    };
+                    PositionEvaluation {
+                        centipawns: GameReview::white_point_of_view(centipawns, position.turn()),
+                        best_move,
+                    }
+                })
+                .collect()
+        })
+        .await
+        .map_err(|err| format!("The built-in review failed: {}", err))
+    }
+
+    fn white_point_of_view(centipawns: i32, side_to_move: Player) -> i32 {
+        match side_to_move {
+            Player::White => centipawns,
+            Player::Black => -centipawns,
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+
+use pleco::{BitMove, Board, PieceType, Player};
+use shakmaty::fen::Fen;
+use shakmaty::{CastlingMode, Chess};
+use shakmaty_syzygy::{Tablebase, Wdl};
+
+pub const MAX_PIECES: usize = 7;
+
+const WDL_EXTENSION: &str = "rtbw";
+const DTZ_EXTENSION: &str = "rtbz";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableCoverage {
+    pub wdl: bool,
+    pub dtz: bool,
+}
+
+/// What the tables say about a legal move, from the point of view of the side making it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProbedMove {
+    pub pleco_move: BitMove,
+    /// The result once the fifty-move counter has been reset, as after a capture or pawn move.
+    pub wdl: Option<Wdl>,
+    /// Plies to the next capture or pawn move, possibly rounded by one.
+    pub dtz: Option<i32>,
+}
+
+pub struct SyzygyDirectory {
+    wdl_tables: HashSet<String>,
+    dtz_tables: HashSet<String>,
+    tablebase: Tablebase<Chess>,
+}
+
+impl SyzygyDirectory {
+    pub fn scan(path: &str) -> io::Result<Self> {
+        let mut wdl_tables = HashSet::new();
+        let mut dtz_tables = HashSet::new();
+        for entry in fs::read_dir(path)? {
+            let file_name = entry?.file_name();
+            let file_name = file_name.to_string_lossy();
+            match file_name.rsplit_once('.') {
+                Some((material, WDL_EXTENSION)) => {
+                    wdl_tables.insert(material.to_string());
+                }
+                Some((material, DTZ_EXTENSION)) => {
+                    dtz_tables.insert(material.to_string());
+                }
+                _ => {}
+            }
+        }
+        let mut tablebase = Tablebase::new();
+        tablebase.add_directory(path)?;
+        Ok(Self {
+            wdl_tables,
+            dtz_tables,
+            tablebase,
+        })
+    }
+
+    pub fn wdl_count(&self) -> usize {
+        self.wdl_tables.len()
+    }
+
+    pub fn dtz_count(&self) -> usize {
+        self.dtz_tables.len()
+    }
+
+    pub fn coverage(&self, board: &Board) -> Option<TableCoverage> {
+        if board.count_all_pieces() as usize > MAX_PIECES {
+            return None;
+        }
+        // Tables are only stored with the stronger side first, so the mirrored name is tried too.
+        let names = [
+            SyzygyDirectory::material_key(board, Player::White),
+            SyzygyDirectory::material_key(board, Player::Black),
+        ];
+        Some(TableCoverage {
+            wdl: names.iter().any(|name| self.wdl_tables.contains(name)),
+            dtz: names.iter().any(|name| self.dtz_tables.contains(name)),
+        })
+    }
+
+    /// Probes every legal move of `board`, best result first. Values are missing when the tables
+    /// needed for the position reached are not in the directory.
+    pub fn probe_moves(&self, board: &Board) -> Vec<ProbedMove> {
+        let mut probed_moves: Vec<ProbedMove> = board
+            .generate_moves()
+            .iter()
+            .map(|pleco_move| {
+                let mut board_after = board.clone();
+                board_after.apply_move(*pleco_move);
+                let position = SyzygyDirectory::to_position(&board_after);
+                // The tables answer for the side to move after the move, that is the opponent.
+                ProbedMove {
+                    pleco_move: *pleco_move,
+                    wdl: position.as_ref().and_then(|position| {
+                        self.tablebase
+                            .probe_wdl_after_zeroing(position)
+                            .ok()
+                            .map(|wdl| -wdl)
+                    }),
+                    dtz: position.as_ref().and_then(|position| {
+                        self.tablebase
+                            .probe_dtz(position)
+                            .ok()
+                            .map(|dtz| -dtz.ignore_rounding().0)
+                    }),
+                }
+            })
+            .collect();
+        probed_moves.sort_by_key(|probed_move| Reverse(probed_move.wdl));
+        probed_moves
+    }
+
+    fn to_position(board: &Board) -> Option<Chess> {
+        Fen::from_ascii(board.fen().as_bytes())
+            .ok()?
+            .into_position(CastlingMode::Standard)
+            .ok()
+    }
+
+    pub fn material_key(board: &Board, first: Player) -> String {
+        let side = |player: Player| {
+            [
+                PieceType::K,
+                PieceType::Q,
+                PieceType::R,
+                PieceType::B,
+                PieceType::N,
+                PieceType::P,
+            ]
+            .into_iter()
+            .map(|piece_type| {
+                piece_type
+                    .char_upper()
+                    .to_string()
+                    .repeat(board.count_piece(player, piece_type) as usize)
+            })
+            .collect::<String>()
+        };
+        format!("{}v{}", side(first), side(first.other_player()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_directory() -> SyzygyDirectory {
+        let path = std::env::temp_dir().join(format!("syzygy-test-{}", std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        let directory = SyzygyDirectory::scan(path.to_str().unwrap()).unwrap();
+        fs::remove_dir(&path).unwrap();
+        directory
+    }
+
+    fn probe(directory: &SyzygyDirectory, fen: &str) -> Vec<(String, Option<Wdl>, Option<i32>)> {
+        let board = Board::from_fen(fen).unwrap();
+        directory
+            .probe_moves(&board)
+            .into_iter()
+            .map(|probed| (probed.pleco_move.stringify(), probed.wdl, probed.dtz))
+            .collect()
+    }
+
+    #[test]
+    fn material_keys_put_the_requested_side_first() {
+        let board = Board::from_fen("8/8/8/4k3/8/8/3PQ3/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            SyzygyDirectory::material_key(&board, Player::White),
+            "KQPvK"
+        );
+        assert_eq!(
+            SyzygyDirectory::material_key(&board, Player::Black),
+            "KvKQP"
+        );
+    }
+
+    #[test]
+    fn coverage_is_only_given_for_tablebase_positions() {
+        let directory = empty_directory();
+        assert_eq!(directory.coverage(&Board::start_pos()), None);
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert_eq!(
+            directory.coverage(&board),
+            Some(TableCoverage {
+                wdl: false,
+                dtz: false
+            })
+        );
+    }
+
+    #[test]
+    fn every_legal_move_is_probed() {
+        let directory = empty_directory();
+        let moves = probe(&directory, "4k3/8/8/8/8/8/3q4/4K3 w - - 0 1");
+        assert_eq!(moves.len(), 2);
+        // Taking the queen leaves two bare kings, which is a draw without any table.
+        assert_eq!(moves[0], (String::from("e1d2"), Some(Wdl::Draw), Some(0)));
+        // King and queen against king needs a table that is not there.
+        assert_eq!(moves[1], (String::from("e1f1"), None, None));
+    }
+}
@@ -0,0 +1,161 @@
+use std::io::{self, BufRead, Write};
+
+use iced_chessboard::GameStatus;
+use pleco::{Board, Piece, Player, SQ};
+
+use crate::game_controller::GameController;
+use crate::game_record::GameRecord;
+
+const HELP: &str = "Enter moves as SAN (Nf3, O-O, exd8=Q) or UCI (g1f3, e7e8q).
+Commands: fen, pgn, flip, new, help, quit";
+
+/// Plays a game on the console with the same move handling and game record as the board widget.
+pub struct Terminal {
+    game: GameController,
+    black_at_bottom: bool,
+}
+
+impl Terminal {
+    pub fn new(black_at_bottom: bool) -> Self {
+        Self {
+            game: GameController::new(Board::start_pos(), None),
+            black_at_bottom,
+        }
+    }
+
+    pub fn from_fen(fen: &str, black_at_bottom: bool) -> Result<Self, String> {
+        Ok(Self {
+            game: GameController::from_fen(fen).map_err(|err| err.to_string())?,
+            black_at_bottom,
+        })
+    }
+
+    pub fn from_pgn(pgn: &str, black_at_bottom: bool) -> Result<Self, String> {
+        Ok(Self {
+            game: GameController::from_record(GameRecord::from_pgn(pgn)?)?,
+            black_at_bottom,
+        })
+    }
+
+    pub fn run(&mut self) -> io::Result<()> {
+        let stdin = io::stdin();
+        let mut stdout = io::stdout();
+        writeln!(stdout, "{}\n", HELP)?;
+        writeln!(stdout, "{}", self.render())?;
+        loop {
+            write!(stdout, "> ")?;
+            stdout.flush()?;
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            match line.trim() {
+                "" => continue,
+                "quit" | "exit" => return Ok(()),
+                "help" => writeln!(stdout, "{}", HELP)?,
+                "fen" => writeln!(stdout, "{}", self.game.fen())?,
+                "pgn" => writeln!(
+                    stdout,
+                    "{}",
+                    self.game.record().to_pgn(self.game.status().result())
+                )?,
+                "flip" => {
+                    self.black_at_bottom = !self.black_at_bottom;
+                    writeln!(stdout, "{}", self.render())?;
+                }
+                "new" => {
+                    *self = Terminal::new(self.black_at_bottom);
+                    writeln!(stdout, "{}", self.render())?;
+                }
+                input => match self.play(input) {
+                    Ok(()) => writeln!(stdout, "{}", self.render())?,
+                    Err(error) => writeln!(stdout, "{}", error)?,
+                },
+            }
+        }
+    }
+
+    /// Plays a move given in SAN or UCI notation and records it.
+    pub fn play(&mut self, input: &str) -> Result<(), String> {
+        if self.game.status() != GameStatus::Ongoing {
+            return Err(String::from(
+                "The game is over, type new to start another one",
+            ));
+        }
+        self.game
+            .play_notation(input)
+            .map(|_| ())
+            .ok_or_else(|| format!("{} is not a legal move", input))
+    }
+
+    pub fn render(&self) -> String {
+        let ranks: Vec<u8> = if self.black_at_bottom {
+            (0..8).collect()
+        } else {
+            (0..8).rev().collect()
+        };
+        let files: Vec<u8> = if self.black_at_bottom {
+            (0..8).rev().collect()
+        } else {
+            (0..8).collect()
+        };
+        let file_labels: String = files
+            .iter()
+            .map(|file| format!(" {}", (b'a' + file) as char))
+            .collect();
+
+        let mut output = String::new();
+        for rank in &ranks {
+            output.push_str(&format!("{} ", rank + 1));
+            for file in &files {
+                let piece = self.game.board().piece_at_sq(SQ(rank * 8 + file));
+                output.push_str(&format!(" {}", Terminal::piece_symbol(piece)));
+            }
+            output.push('\n');
+        }
+        output.push_str(&format!("  {}\n", file_labels));
+        output.push_str(&self.status());
+        output
+    }
+
+    fn status(&self) -> String {
+        match self.game.status() {
+            GameStatus::Checkmate {
+                winner: Player::White,
+            } => String::from("Checkmate, White wins 1-0"),
+            GameStatus::Checkmate {
+                winner: Player::Black,
+            } => String::from("Checkmate, Black wins 0-1"),
+            GameStatus::Stalemate => String::from("Stalemate 1/2-1/2"),
+            GameStatus::Ongoing => {
+                let side = match self.game.turn() {
+                    Player::White => "White",
+                    Player::Black => "Black",
+                };
+                if self.game.board().in_check() {
+                    format!("{} to move, in check", side)
+                } else {
+                    format!("{} to move", side)
+                }
+            }
+        }
+    }
+
+    fn piece_symbol(piece: Piece) -> char {
+        match piece {
+            Piece::WhiteKing => '♔',
+            Piece::WhiteQueen => '♕',
+            Piece::WhiteRook => '♖',
+            Piece::WhiteBishop => '♗',
+            Piece::WhiteKnight => '♘',
+            Piece::WhitePawn => '♙',
+            Piece::BlackKing => '♚',
+            Piece::BlackQueen => '♛',
+            Piece::BlackRook => '♜',
+            Piece::BlackBishop => '♝',
+            Piece::BlackKnight => '♞',
+            Piece::BlackPawn => '♟',
+            Piece::None => '·',
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use iced::futures::channel::mpsc;
+use iced::futures::StreamExt;
+use iced::subscription::{self, Subscription};
+
+use super::{AnalysisInfo, UciEngine};
+
+#[derive(Debug, Clone)]
+pub enum AnalysisEvent {
+    Ready(mpsc::Sender<AnalysisCommand>, Option<String>),
+    Info(String, AnalysisInfo),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum AnalysisCommand {
+    Analyze {
+        start_fen: String,
+        moves: Vec<String>,
+        position_fen: String,
+        chess960: bool,
+    },
+    Stop,
+}
+
+enum State {
+    Starting(String),
+    Ready {
+        engine: Box<UciEngine>,
+        receiver: mpsc::Receiver<AnalysisCommand>,
+        analyzed_fen: Option<String>,
+    },
+    Failed,
+}
+
+pub fn analysis(engine_path: String) -> Subscription<AnalysisEvent> {
+    struct Analysis;
+
+    subscription::unfold(
+        (std::any::TypeId::of::<Analysis>(), engine_path.clone()),
+        State::Starting(engine_path),
+        |state| async move {
+            match state {
+                State::Starting(engine_path) => match UciEngine::start(&engine_path).await {
+                    Ok(engine) => {
+                        let (sender, receiver) = mpsc::channel(100);
+                        let name = engine.name().map(String::from);
+                        (
+                            Some(AnalysisEvent::Ready(sender, name)),
+                            State::Ready {
+                                engine: Box::new(engine),
+                                receiver,
+                                analyzed_fen: None,
+                            },
+                        )
+                    }
+                    Err(err) => (
+                        Some(AnalysisEvent::Failed(format!(
+                            "Could not start {}: {}",
+                            engine_path, err
+                        ))),
+                        State::Failed,
+                    ),
+                },
+                State::Ready {
+                    mut engine,
+                    mut receiver,
+                    analyzed_fen,
+                } => {
+                    let analyzing = analyzed_fen.is_some();
+                    tokio::select! {
+                        command = receiver.next() => {
+                            let result = match command {
+                                Some(command) => {
+                                    handle_command(&mut engine, command, analyzing).await
+                                }
+                                None => {
+                                    engine.quit().await;
+                                    return (None, State::Failed);
+                                }
+                            };
+                            match result {
+                                Ok(analyzed_fen) => (
+                                    None,
+                                    State::Ready {
+                                        engine,
+                                        receiver,
+                                        analyzed_fen,
+                                    },
+                                ),
+                                Err(err) => (
+                                    Some(AnalysisEvent::Failed(err.to_string())),
+                                    State::Failed,
+                                ),
+                            }
+                        }
+                        line = engine.read_line(), if analyzing => match line {
+                            Ok(line) if UciEngine::parse_best_move(&line).is_some() => {
+                                // The engine ended the search on its own, so there is nothing
+                                // left to stop.
+                                (
+                                    None,
+                                    State::Ready {
+                                        engine,
+                                        receiver,
+                                        analyzed_fen: None,
+                                    },
+                                )
+                            }
+                            Ok(line) => {
+                                let event = AnalysisInfo::parse(&line).and_then(|info| {
+                                    analyzed_fen
+                                        .clone()
+                                        .map(|fen| AnalysisEvent::Info(fen, info))
+                                });
+                                (
+                                    event,
+                                    State::Ready {
+                                        engine,
+                                        receiver,
+                                        analyzed_fen,
+                                    },
+                                )
+                            }
+                            Err(err) => (
+                                Some(AnalysisEvent::Failed(err.to_string())),
+                                State::Failed,
+                            ),
+                        },
+                    }
+                }
+                State::Failed => iced::futures::future::pending().await,
+            }
+        },
+    )
+}
+
+async fn handle_command(
+    engine: &mut UciEngine,
+    command: AnalysisCommand,
+    analyzing: bool,
+) -> std::io::Result<Option<String>> {
+    if analyzing {
+        engine.stop().await?;
+    }
+    match command {
+        AnalysisCommand::Analyze {
+            start_fen,
+            moves,
+            position_fen,
+            chess960,
+        } => {
+            engine
+                .set_option("UCI_Chess960", if chess960 { "true" } else { "false" })
+                .await?;
+            engine.wait_ready().await?;
+            engine.set_position(&start_fen, &moves).await?;
+            engine.go_infinite().await?;
+            Ok(Some(position_fen))
+        }
+        AnalysisCommand::Stop => Ok(None),
+    }
+}
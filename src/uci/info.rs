@@ -0,0 +1,77 @@
+use std::fmt;
+
+use pleco::Player;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Score {
+    Centipawns(i32),
+    Mate(i32),
+}
+
+impl Score {
+    pub fn white_point_of_view(self, side_to_move: Player) -> Score {
+        if side_to_move == Player::White {
+            return self;
+        }
+        match self {
+            Score::Centipawns(centipawns) => Score::Centipawns(-centipawns),
+            Score::Mate(moves) => Score::Mate(-moves),
+        }
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Score::Centipawns(centipawns) => write!(f, "{:+.2}", *centipawns as f32 / 100.0),
+            Score::Mate(moves) => write!(f, "#{}", moves),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnalysisInfo {
+    pub depth: Option<u32>,
+    pub multipv: Option<u32>,
+    pub score: Option<Score>,
+    pub nodes: Option<u64>,
+    pub pv: Vec<String>,
+}
+
+impl AnalysisInfo {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("info") {
+            return None;
+        }
+
+        let mut info = AnalysisInfo::default();
+        while let Some(token) = tokens.next() {
+            match token {
+                "depth" => info.depth = tokens.next().and_then(|value| value.parse().ok()),
+                "multipv" => info.multipv = tokens.next().and_then(|value| value.parse().ok()),
+                "nodes" => info.nodes = tokens.next().and_then(|value| value.parse().ok()),
+                "score" => {
+                    let kind = tokens.next();
+                    let value = tokens.next().and_then(|value| value.parse().ok());
+                    info.score = match (kind, value) {
+                        (Some("cp"), Some(value)) => Some(Score::Centipawns(value)),
+                        (Some("mate"), Some(value)) => Some(Score::Mate(value)),
+                        _ => None,
+                    };
+                }
+                "pv" => {
+                    info.pv = tokens.by_ref().map(String::from).collect();
+                }
+                "string" => return None,
+                _ => {}
+            }
+        }
+
+        if info.score.is_none() && info.pv.is_empty() {
+            None
+        } else {
+            Some(info)
+        }
+    }
+}
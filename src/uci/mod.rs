@@ -0,0 +1,306 @@
+mod analysis;
+pub use analysis::{analysis, AnalysisCommand, AnalysisEvent};
+
+mod info;
+pub use info::{AnalysisInfo, Score};
+
+mod opponent;
+pub use opponent::{opponent, ClockTimes, OpponentCommand, OpponentEvent, SearchLimit};
+
+use std::io;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct UciEngine {
+    _process: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    name: Option<String>,
+}
+
+impl UciEngine {
+    pub async fn start(path: &str) -> io::Result<Self> {
+        let mut process = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdin = process
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "engine stdin is closed"))?;
+        let stdout = process
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "engine stdout is closed"))?;
+
+        let mut engine = Self {
+            _process: process,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            name: None,
+        };
+        tokio::time::timeout(HANDSHAKE_TIMEOUT, engine.handshake())
+            .await
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "the engine did not answer the UCI handshake",
+                )
+            })??;
+        Ok(engine)
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub async fn set_option(&mut self, name: &str, value: &str) -> io::Result<()> {
+        self.send(&format!("setoption name {} value {}", name, value))
+            .await
+    }
+
+    pub async fn wait_ready(&mut self) -> io::Result<()> {
+        self.send("isready").await?;
+        while self.read_line().await?.trim() != "readyok" {}
+        Ok(())
+    }
+
+    pub async fn set_position(&mut self, fen: &str, moves: &[String]) -> io::Result<()> {
+        let mut command = format!("position fen {}", fen);
+        if !moves.is_empty() {
+            command.push_str(" moves ");
+            command.push_str(&moves.join(" "));
+        }
+        self.send(&command).await
+    }
+
+    pub async fn go_infinite(&mut self) -> io::Result<()> {
+        self.send("go infinite").await
+    }
+
+    pub async fn go(&mut self, limit: SearchLimit, clock: Option<ClockTimes>) -> io::Result<()> {
+        let mut command = match limit {
+            SearchLimit::Depth(depth) => format!("go depth {}", depth),
+            SearchLimit::MoveTime(movetime) => format!("go movetime {}", movetime.as_millis()),
+        };
+        if let Some(clock) = clock {
+            command.push_str(&format!(
+                " wtime {} btime {} winc {} binc {}",
+                clock.white.as_millis(),
+                clock.black.as_millis(),
+                clock.increment.as_millis(),
+                clock.increment.as_millis()
+            ));
+        }
+        self.send(&command).await
+    }
+
+    pub async fn evaluate(
+        &mut self,
+        limit: SearchLimit,
+    ) -> io::Result<(Option<Score>, Option<String>)> {
+        self.go(limit, None).await?;
+        let mut score = None;
+        loop {
+            let line = self.read_line().await?;
+            if let Some(info) = AnalysisInfo::parse(&line) {
+                if info.multipv.unwrap_or(1) == 1 && info.score.is_some() {
+                    score = info.score;
+                }
+                continue;
+            }
+            if let Some(best_move) = UciEngine::parse_best_move(&line) {
+                return Ok((score, best_move));
+            }
+        }
+    }
+
+    pub async fn stop(&mut self) -> io::Result<Option<String>> {
+        self.send("stop").await?;
+        self.wait_best_move().await
+    }
+
+    pub async fn wait_best_move(&mut self) -> io::Result<Option<String>> {
+        loop {
+            let line = self.read_line().await?;
+            if let Some(best_move) = UciEngine::parse_best_move(&line) {
+                return Ok(best_move);
+            }
+        }
+    }
+
+    pub async fn read_line(&mut self) -> io::Result<String> {
+        self.stdout.next_line().await?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the engine process has exited",
+            )
+        })
+    }
+
+    pub async fn quit(mut self) {
+        let _ = self.send("quit").await;
+    }
+
+    async fn handshake(&mut self) -> io::Result<()> {
+        self.send("uci").await?;
+        loop {
+            let line = self.read_line().await?;
+            let line = line.trim();
+            if let Some(name) = line.strip_prefix("id name ") {
+                self.name = Some(name.to_string());
+            }
+            if line == "uciok" {
+                break;
+            }
+        }
+        self.wait_ready().await
+    }
+
+    fn parse_best_move(line: &str) -> Option<Option<String>> {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("bestmove") {
+            return None;
+        }
+        Some(
+            tokens
+                .next()
+                .filter(|best_move| *best_move != "(none)" && *best_move != "0000")
+                .map(String::from),
+        )
+    }
+
+    async fn send(&mut self, command: &str) -> io::Result<()> {
+        self.stdin.write_all(command.as_bytes()).await?;
+        self.stdin.write_all(b"\n").await?;
+        self.stdin.flush().await
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn fake_engine_path() -> String {
+        format!("{}/tests/fake_uci_engine.sh", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[test]
+    fn parses_centipawns_info_line() {
+        let info = AnalysisInfo::parse(
+            "info depth 12 seldepth 18 multipv 1 score cp 34 nodes 12000 nps 1000 pv e2e4 e7e5 g1f3",
+        )
+        .unwrap();
+        assert_eq!(info.depth, Some(12));
+        assert_eq!(info.multipv, Some(1));
+        assert_eq!(info.score, Some(Score::Centipawns(34)));
+        assert_eq!(info.nodes, Some(12000));
+        assert_eq!(info.pv, vec!["e2e4", "e7e5", "g1f3"]);
+    }
+
+    #[test]
+    fn parses_mate_info_line_with_bound() {
+        let info = AnalysisInfo::parse("info depth 5 score mate -2 lowerbound pv h7h8").unwrap();
+        assert_eq!(info.score, Some(Score::Mate(-2)));
+        assert_eq!(info.pv, vec!["h7h8"]);
+    }
+
+    #[test]
+    fn ignores_lines_without_evaluation() {
+        assert_eq!(AnalysisInfo::parse("info string NNUE enabled"), None);
+        assert_eq!(AnalysisInfo::parse("info depth 3 currmove e2e4"), None);
+        assert_eq!(AnalysisInfo::parse("bestmove e2e4"), None);
+    }
+
+    #[test]
+    fn recognises_best_move_lines() {
+        assert_eq!(
+            UciEngine::parse_best_move("bestmove e2e4 ponder e7e5"),
+            Some(Some(String::from("e2e4")))
+        );
+        assert_eq!(UciEngine::parse_best_move("bestmove (none)"), Some(None));
+        assert_eq!(UciEngine::parse_best_move("info depth 3 pv e2e4"), None);
+    }
+
+    #[test]
+    fn score_is_flipped_for_black_to_move() {
+        assert_eq!(
+            Score::Centipawns(50).white_point_of_view(pleco::Player::Black),
+            Score::Centipawns(-50)
+        );
+        assert_eq!(
+            Score::Mate(3).white_point_of_view(pleco::Player::White),
+            Score::Mate(3)
+        );
+    }
+
+    #[tokio::test]
+    async fn handshake_reads_engine_name() {
+        let engine = UciEngine::start(&fake_engine_path()).await.unwrap();
+        assert_eq!(engine.name(), Some("Fake engine"));
+        engine.quit().await;
+    }
+
+    #[tokio::test]
+    async fn infinite_analysis_reports_infos_until_stopped() {
+        let mut engine = UciEngine::start(&fake_engine_path()).await.unwrap();
+        engine
+            .set_position(
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                &[String::from("e2e4")],
+            )
+            .await
+            .unwrap();
+        engine.go_infinite().await.unwrap();
+
+        let mut infos = vec![];
+        while infos.len() < 2 {
+            if let Some(info) = AnalysisInfo::parse(&engine.read_line().await.unwrap()) {
+                infos.push(info);
+            }
+        }
+        assert_eq!(infos[1].score, Some(Score::Centipawns(25)));
+        assert_eq!(infos[1].pv, vec!["e7e5", "g1f3"]);
+
+        let best_move = engine.stop().await.unwrap();
+        assert_eq!(best_move, Some(String::from("e7e5")));
+        engine.quit().await;
+    }
+
+    #[tokio::test]
+    async fn limited_search_returns_best_move() {
+        let mut engine = UciEngine::start(&fake_engine_path()).await.unwrap();
+        engine.set_option("Skill Level", "5").await.unwrap();
+        engine
+            .set_position(
+                "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+                &[],
+            )
+            .await
+            .unwrap();
+        let clock = ClockTimes {
+            white: Duration::from_secs(60),
+            black: Duration::from_secs(30),
+            increment: Duration::from_secs(1),
+        };
+        engine.go(SearchLimit::Depth(4), Some(clock)).await.unwrap();
+        assert_eq!(
+            engine.wait_best_move().await.unwrap(),
+            Some(String::from("e7e5"))
+        );
+        engine.quit().await;
+    }
+
+    #[tokio::test]
+    async fn missing_engine_is_reported() {
+        let result = UciEngine::start("/nonexistent/uci/engine").await;
+        assert!(result.is_err());
+    }
+}
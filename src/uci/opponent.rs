@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use iced::futures::channel::mpsc;
+use iced::futures::StreamExt;
+use iced::subscription::{self, Subscription};
+
+use super::UciEngine;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchLimit {
+    Depth(u32),
+    MoveTime(Duration),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockTimes {
+    pub white: Duration,
+    pub black: Duration,
+    pub increment: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub enum OpponentEvent {
+    Ready(mpsc::Sender<OpponentCommand>, Option<String>),
+    BestMove(String, Option<String>),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum OpponentCommand {
+    Play {
+        start_fen: String,
+        moves: Vec<String>,
+        position_fen: String,
+        chess960: bool,
+        limit: SearchLimit,
+        skill_level: Option<u8>,
+        clock: Option<ClockTimes>,
+    },
+    /// Abandons the current search without reporting its move.
+    Stop,
+}
+
+enum State {
+    Starting(String),
+    Ready {
+        engine: Box<UciEngine>,
+        receiver: mpsc::Receiver<OpponentCommand>,
+        searched_fen: Option<String>,
+    },
+    Failed,
+}
+
+pub fn opponent(engine_path: String) -> Subscription<OpponentEvent> {
+    struct Opponent;
+
+    subscription::unfold(
+        (std::any::TypeId::of::<Opponent>(), engine_path.clone()),
+        State::Starting(engine_path),
+        |state| async move {
+            match state {
+                State::Starting(engine_path) => match UciEngine::start(&engine_path).await {
+                    Ok(engine) => {
+                        let (sender, receiver) = mpsc::channel(100);
+                        let name = engine.name().map(String::from);
+                        (
+                            Some(OpponentEvent::Ready(sender, name)),
+                            State::Ready {
+                                engine: Box::new(engine),
+                                receiver,
+                                searched_fen: None,
+                            },
+                        )
+                    }
+                    Err(err) => (
+                        Some(OpponentEvent::Failed(format!(
+                            "Could not start {}: {}",
+                            engine_path, err
+                        ))),
+                        State::Failed,
+                    ),
+                },
+                State::Ready {
+                    mut engine,
+                    mut receiver,
+                    searched_fen,
+                } => {
+                    let searching = searched_fen.is_some();
+                    tokio::select! {
+                        command = receiver.next() => {
+                            let result = match command {
+                                Some(command) => play(&mut engine, command, searching).await,
+                                None => {
+                                    engine.quit().await;
+                                    return (None, State::Failed);
+                                }
+                            };
+                            match result {
+                                Ok(searched_fen) => (
+                                    None,
+                                    State::Ready {
+                                        engine,
+                                        receiver,
+                                        searched_fen,
+                                    },
+                                ),
+                                Err(err) => {
+                                    (Some(OpponentEvent::Failed(err.to_string())), State::Failed)
+                                }
+                            }
+                        }
+                        line = engine.read_line(), if searching => match line {
+                            Ok(line) => {
+                                let event = UciEngine::parse_best_move(&line).and_then(|best_move| {
+                                    searched_fen
+                                        .clone()
+                                        .map(|fen| OpponentEvent::BestMove(fen, best_move))
+                                });
+                                let searched_fen = if event.is_some() { None } else { searched_fen };
+                                (
+                                    event,
+                                    State::Ready {
+                                        engine,
+                                        receiver,
+                                        searched_fen,
+                                    },
+                                )
+                            }
+                            Err(err) => (Some(OpponentEvent::Failed(err.to_string())), State::Failed),
+                        },
+                    }
+                }
+                State::Failed => iced::futures::future::pending().await,
+            }
+        },
+    )
+}
+
+/// Starts the search asked by `command`, after abandoning the current one, and returns the
+/// position now searched.
+async fn play(
+    engine: &mut UciEngine,
+    command: OpponentCommand,
+    searching: bool,
+) -> std::io::Result<Option<String>> {
+    if searching {
+        engine.stop().await?;
+    }
+    match command {
+        OpponentCommand::Play {
+            start_fen,
+            moves,
+            position_fen,
+            chess960,
+            limit,
+            skill_level,
+            clock,
+        } => {
+            engine
+                .set_option("UCI_Chess960", if chess960 { "true" } else { "false" })
+                .await?;
+            if let Some(skill_level) = skill_level {
+                engine
+                    .set_option("Skill Level", &skill_level.to_string())
+                    .await?;
+            }
+            engine.wait_ready().await?;
+            engine.set_position(&start_fen, &moves).await?;
+            engine.go(limit, clock).await?;
+            Ok(Some(position_fen))
+        }
+        OpponentCommand::Stop => Ok(None),
+    }
+}
@@ -0,0 +1,36 @@
+#!/bin/sh
+# Minimal scripted UCI engine used by the unit tests of the uci module.
+
+searching=0
+
+while IFS= read -r command; do
+    case "$command" in
+        uci)
+            echo "id name Fake engine"
+            echo "id author Peer chess"
+            echo "option name Skill Level type spin default 20 min 0 max 20"
+            echo "uciok"
+            ;;
+        isready)
+            echo "readyok"
+            ;;
+        "go infinite")
+            searching=1
+            echo "info depth 1 score cp 10 nodes 20 pv e7e5"
+            echo "info depth 2 seldepth 3 score cp 25 nodes 400 pv e7e5 g1f3"
+            ;;
+        go*)
+            echo "info depth 1 score cp 10 nodes 20 pv e7e5"
+            echo "bestmove e7e5"
+            ;;
+        stop)
+            if [ "$searching" = "1" ]; then
+                searching=0
+                echo "bestmove e7e5 ponder g1f3"
+            fi
+            ;;
+        quit)
+            exit 0
+            ;;
+    esac
+done